@@ -0,0 +1,331 @@
+//! Feeds arbitrary raw values through every register type and checks that
+//! - `from_bits`/`bits` and the `From` conversions round-trip the raw value exactly, and
+//! - reading every field and rebuilding the register from zero with the `with_*` builders
+//!   reproduces the raw value on all the bits covered by `USED_BITS`, and
+//! - the multi-bit [`Parity`] accessors of the line control register agree with each other and with
+//!   [`LineControlBuilder`], and
+//! - [`BaudRateDivisor::new`] accepts exactly the divisor register pairs the TRM allows, and
+//! - [`DataRegister::status`] reports exactly the error flags that are set, and `received` round-trips them, and
+//! - [`IrDALowPowerRegister::for_clock`] only returns divisors inside the IrLPBaud16 window, and
+//! - [`WordLength::from_data_bits`] accepts exactly 5 to 8 data bits and agrees with `data_bits`, and
+//! - any mode string that parses formats back to itself, apart from the case of the parity letter.
+//!
+//! Run with `cargo fuzz run register_round_trip` from the repository root.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pl011_uart_registers::baud::{BaudError, BaudRateDivisor};
+use pl011_uart_registers::blocking::ReceiveError;
+use pl011_uart_registers::frame::FrameError;
+use pl011_uart_registers::irda::IrdaError;
+use pl011_uart_registers::mode::parse_mode;
+use pl011_uart_registers::registrers::*;
+
+/// Checks one register type. Every field is copied over with its getter and builder.
+/// The field check is skipped when one of the `fallible` fields doesn't decode,
+/// as there is no value to pass to its builder.
+macro_rules! round_trip {
+    (
+        $raw:expr, $register:ident: $bits:ty {
+            $($field:ident => $with:ident,)*
+        } $(fallible {
+            $($fallible_field:ident => $fallible_with:ident,)*
+        })?
+    ) => {{
+        let raw = $raw as $bits;
+        let register = $register::from_bits(raw);
+        assert_eq!(register.bits(), raw, "{} lost bits", stringify!($register));
+        assert_eq!($register::from_bits(register.bits()).bits(), raw);
+        assert_eq!(<$bits>::from($register::from(raw)), raw);
+        #[allow(unused_labels)] // only used when there are fallible fields
+        let rebuilt = 'rebuild: {
+            let rebuilt = $register::from_bits(0) $(.$with(register.$field()))*;
+            $($(
+                let Ok(value) = register.$fallible_field() else {
+                    break 'rebuild None;
+                };
+                let rebuilt = rebuilt.$fallible_with(value);
+            )*)?
+            Some(rebuilt)
+        };
+        if let Some(rebuilt) = rebuilt {
+            assert_eq!(
+                rebuilt.bits() & $register::USED_BITS,
+                raw & $register::USED_BITS,
+                "{} fields don't round-trip",
+                stringify!($register)
+            );
+        }
+    }};
+}
+
+fuzz_target!(|raw: u32| {
+    round_trip!(raw, DataRegister: u32 {
+        overrun_error => with_overrun_error,
+        break_error => with_break_error,
+        parity_error => with_parity_error,
+        framing_error => with_framing_error,
+        data => with_data,
+    });
+    round_trip!(raw, ReceiveStatusRegister: u32 {
+        overrun_error => with_overrun_error,
+        break_error => with_break_error,
+        parity_error => with_parity_error,
+        framing_error => with_framing_error,
+    });
+    round_trip!(raw, FlagRegister: u32 {
+        ring_indicator => with_ring_indicator,
+        transmit_fifo_empty => with_transmit_fifo_empty,
+        receive_fifo_full => with_receive_fifo_full,
+        transmit_fifo_full => with_transmit_fifo_full,
+        receive_fifo_empty => with_receive_fifo_empty,
+        uart_busy => with_uart_busy,
+        data_carrier_detect => with_data_carrier_detect,
+        data_set_ready => with_data_set_ready,
+        clear_to_send => with_clear_to_send,
+    });
+    round_trip!(raw, IrDALowPowerRegister: u8 {
+    } fallible {
+        low_power_divisor_value => with_low_power_divisor_value,
+    });
+    round_trip!(raw, IntegerBaudRateDivisorRegister: u16 {
+    } fallible {
+        integer_baud_rate_divisor => with_integer_baud_rate_divisor,
+    });
+    round_trip!(raw, FractionalBaudRateDivisorRegister: u8 {
+        fractional_baud_rate_divisor => with_fractional_baud_rate_divisor,
+    });
+    round_trip!(raw, LineControlRegister: u16 {
+        stick_parity => with_stick_parity,
+        word_length => with_word_length,
+        enable_fifos => with_enable_fifos,
+        stop_bits => with_stop_bits,
+        parity_polarity => with_parity_polarity,
+        parity_enable => with_parity_enable,
+        send_break => with_send_break,
+    });
+    round_trip!(raw, ControlRegister: u16 {
+        cts_hardware_flow_control_enable => with_cts_hardware_flow_control_enable,
+        rts_hardware_flow_control_enable => with_rts_hardware_flow_control_enable,
+        out2 => with_out2,
+        out1 => with_out1,
+        request_to_send => with_request_to_send,
+        data_transmit_ready => with_data_transmit_ready,
+        receive_enable => with_receive_enable,
+        transmit_enable => with_transmit_enable,
+        loopback_enable => with_loopback_enable,
+        sir_enable => with_sir_enable,
+        uart_enable => with_uart_enable,
+    });
+    round_trip!(raw, InterruptFIFOLevelSelectRegister: u16 {
+    } fallible {
+        receive_interrupt_fifo_level_select => with_receive_interrupt_fifo_level_select,
+        transmit_interrupt_fifo_level_select => with_transmit_interrupt_fifo_level_select,
+    });
+    round_trip!(raw, InterruptMaskSetClearRegister: u16 {
+        overrun_error_interrupt_mask => with_overrun_error_interrupt_mask,
+        break_error_interrupt_mask => with_break_error_interrupt_mask,
+        parity_error_interrupt_mask => with_parity_error_interrupt_mask,
+        framing_error_interrupt_mask => with_framing_error_interrupt_mask,
+        receive_timeout_interrupt_mask => with_receive_timeout_interrupt_mask,
+        transmit_interrupt_mask => with_transmit_interrupt_mask,
+        receive_interrupt_mask => with_receive_interrupt_mask,
+        n_uartdsr_modem_interrupt_mask => with_n_uartdsr_modem_interrupt_mask,
+        n_uartdcd_modem_interrupt_mask => with_n_uartdcd_modem_interrupt_mask,
+        n_uartcts_modem_interrupt_mask => with_n_uartcts_modem_interrupt_mask,
+        n_uartri_modem_interrupt_mask => with_n_uartri_modem_interrupt_mask,
+    });
+    round_trip!(raw, RawInterruptStatusRegister: u16 {
+        overrun_error_raw_interrupt => with_overrun_error_raw_interrupt,
+        break_error_raw_interrupt => with_break_error_raw_interrupt,
+        parity_error_raw_interrupt => with_parity_error_raw_interrupt,
+        framing_error_raw_interrupt => with_framing_error_raw_interrupt,
+        receive_timeout_raw_interrupt => with_receive_timeout_raw_interrupt,
+        transmit_raw_interrupt => with_transmit_raw_interrupt,
+        receive_raw_interrupt => with_receive_raw_interrupt,
+        n_uartdsr_modem_raw_interrupt => with_n_uartdsr_modem_raw_interrupt,
+        n_uartdcd_modem_raw_interrupt => with_n_uartdcd_modem_raw_interrupt,
+        n_uartcts_modem_raw_interrupt => with_n_uartcts_modem_raw_interrupt,
+        n_uartri_modem_raw_interrupt => with_n_uartri_modem_raw_interrupt,
+    });
+    round_trip!(raw, MaskedInterruptStatusRegister: u16 {
+        overrun_error_interrupt_status => with_overrun_error_interrupt_status,
+        break_error_interrupt_status => with_break_error_interrupt_status,
+        parity_error_interrupt_status => with_parity_error_interrupt_status,
+        framing_error_interrupt_status => with_framing_error_interrupt_status,
+        receive_timeout_interrupt_status => with_receive_timeout_interrupt_status,
+        transmit_interrupt_status => with_transmit_interrupt_status,
+        receive_interrupt_status => with_receive_interrupt_status,
+        n_uartdsr_modem_interrupt_status => with_n_uartdsr_modem_interrupt_status,
+        n_uartdcd_modem_interrupt_status => with_n_uartdcd_modem_interrupt_status,
+        n_uartcts_modem_interrupt_status => with_n_uartcts_modem_interrupt_status,
+        n_uartri_modem_interrupt_status => with_n_uartri_modem_interrupt_status,
+    });
+    round_trip!(raw, InterruptClearRegister: u16 {
+        clear_overrun_error_interrupt => with_clear_overrun_error_interrupt,
+        clear_break_error_interrupt => with_clear_break_error_interrupt,
+        clear_parity_error_interrupt => with_clear_parity_error_interrupt,
+        clear_framing_error_interrupt => with_clear_framing_error_interrupt,
+        clear_receive_timeout_interrupt => with_clear_receive_timeout_interrupt,
+        clear_transmit_interrupt => with_clear_transmit_interrupt,
+        clear_receive_interrupt => with_clear_receive_interrupt,
+        clear_n_uartdsr_modem_interrupt => with_clear_n_uartdsr_modem_interrupt,
+        clear_n_uartdcd_modem_interrupt => with_clear_n_uartdcd_modem_interrupt,
+        clear_n_uartcts_modem_interrupt => with_clear_n_uartcts_modem_interrupt,
+        clear_n_uartri_modem_interrupt => with_clear_n_uartri_modem_interrupt,
+    });
+    round_trip!(raw, DMAControlRegister: u8 {
+        dma_on_error => with_dma_on_error,
+        transmit_dma_enable => with_transmit_dma_enable,
+        receive_dma_enable => with_receive_dma_enable,
+    });
+    round_trip!(raw, PeripheralId0Register: u8 {
+        part_number_0 => with_part_number_0,
+    });
+    round_trip!(raw, PeripheralId1Register: u8 {
+        designer_0 => with_designer_0,
+        part_number_1 => with_part_number_1,
+    });
+    round_trip!(raw, PeripheralId2Register: u8 {
+        revision => with_revision,
+        designer_1 => with_designer_1,
+    });
+    round_trip!(raw, PeripheralId3Register: u8 {
+        configuration => with_configuration,
+    });
+    round_trip!(raw, PrimeCellId0Register: u8 {
+        primecell_id_0 => with_primecell_id_0,
+    });
+    round_trip!(raw, PrimeCellId1Register: u8 {
+        primecell_id_1 => with_primecell_id_1,
+    });
+    round_trip!(raw, PrimeCellId2Register: u8 {
+        primecell_id_2 => with_primecell_id_2,
+    });
+    round_trip!(raw, PrimeCellId3Register: u8 {
+        primecell_id_3 => with_primecell_id_3,
+    });
+    round_trip!(raw, TestControlRegister: u8 {
+        sir_test => with_sir_test,
+        test_fifo_enable => with_test_fifo_enable,
+        integration_test_enable => with_integration_test_enable,
+    });
+    round_trip!(raw, IntegrationTestInputRegister: u16 {
+        n_uartcts => with_n_uartcts,
+        n_uartdcd => with_n_uartdcd,
+        n_uartdsr => with_n_uartdsr,
+        n_uartri => with_n_uartri,
+        uart_txdmaclr => with_uart_txdmaclr,
+        uart_rxdmaclr => with_uart_rxdmaclr,
+        sirin => with_sirin,
+        uart_rxd => with_uart_rxd,
+    });
+    round_trip!(raw, IntegrationTestOutputRegister: u16 {
+        uart_msintr => with_uart_msintr,
+        uart_rxintr => with_uart_rxintr,
+        uart_txintr => with_uart_txintr,
+        uart_rtintr => with_uart_rtintr,
+        uart_eintr => with_uart_eintr,
+        uart_intr => with_uart_intr,
+        n_uartout2 => with_n_uartout2,
+        n_uartout1 => with_n_uartout1,
+        n_uartrts => with_n_uartrts,
+        n_uartdtr => with_n_uartdtr,
+        uart_txdmasreq => with_uart_txdmasreq,
+        uart_txdmabreq => with_uart_txdmabreq,
+        uart_rxdmasreq => with_uart_rxdmasreq,
+        uart_rxdmabreq => with_uart_rxdmabreq,
+        n_sirout => with_n_sirout,
+        uart_txd => with_uart_txd,
+    });
+    round_trip!(raw, IntegrationTestDataRegister: u16 {
+        overrun_error => with_overrun_error,
+        break_error => with_break_error,
+        parity_error => with_parity_error,
+        framing_error => with_framing_error,
+        data => with_data,
+    });
+    let integer = raw as u16;
+    let fractional = FractionalBaudRateDivisorRegister::from_bits((raw >> 16) as u8 & 0x3F);
+    let (fractional, fractional_bits) =
+        (fractional.fractional_baud_rate_divisor(), fractional.bits());
+    match BaudRateDivisor::new(integer, fractional) {
+        Ok(divisor) => {
+            assert!(integer != 0 && (integer != 0xFFFF || fractional_bits == 0));
+            assert_eq!(
+                (divisor.integer(), divisor.fractional()),
+                (integer, fractional_bits)
+            );
+        }
+        Err(BaudError::TooFast) => assert_eq!(integer, 0),
+        Err(BaudError::TooSlow) => assert!(integer == 0xFFFF && fractional_bits != 0),
+        Err(BaudError::ZeroBaudRate) => unreachable!(),
+    }
+    match IrDALowPowerRegister::for_clock(raw) {
+        Ok(ilpr) => {
+            let divisor = u64::from(ilpr.bits());
+            let clock = u64::from(raw);
+            assert!(1_420_000 * divisor < clock && clock < 2_120_000 * divisor);
+        }
+        Err(IrdaError::ClockTooSlow) => assert!(raw <= 1_420_000),
+        Err(IrdaError::ClockTooFast) => assert!(u64::from(raw) >= 2_120_000 * 255),
+        Err(IrdaError::NoDivisor) => assert!(
+            (2_120_000..=2_840_000).contains(&raw) || (4_240_000..=4_260_000).contains(&raw)
+        ),
+    }
+    let data = DataRegister::from_bits(raw);
+    let errors = data.errors();
+    assert_eq!(errors.contains(FrameError::OVERRUN), data.overrun_error());
+    assert_eq!(errors.contains(FrameError::BREAK), data.break_error());
+    assert_eq!(errors.contains(FrameError::PARITY), data.parity_error());
+    assert_eq!(errors.contains(FrameError::FRAMING), data.framing_error());
+    assert_eq!(data.status().is_ok(), ReceiveError::check(data).is_ok());
+    assert_eq!(
+        DataRegister::from(data.received()).bits(),
+        raw & DataRegister::USED_BITS
+    );
+    match WordLength::from_data_bits(raw as u8) {
+        Some(word_length) => assert_eq!(word_length.data_bits(), raw as u8),
+        None => assert!(!(5..=8).contains(&(raw as u8))),
+    }
+    if let Ok(mode) = core::str::from_utf8(&raw.to_le_bytes()[..3])
+        && let Ok(line_control) = LineControlRegister::from_mode(mode)
+    {
+        assert_eq!(
+            parse_mode(mode),
+            Ok((
+                line_control.word_length(),
+                line_control.parity(),
+                line_control.stop_bits()
+            ))
+        );
+        assert_eq!(
+            line_control.to_mode_string().as_str(),
+            mode.to_ascii_uppercase()
+        );
+    }
+    let line_control = LineControlRegister::from_bits(raw as u16);
+    for &parity in Parity::all() {
+        assert_eq!(line_control.with_parity(parity).parity(), parity);
+        let mode = line_control.with_parity(parity).to_mode_string();
+        let parsed = LineControlRegister::from_mode(mode.as_str()).unwrap();
+        assert_eq!(parsed.parity(), parity);
+        assert_eq!(parsed.word_length(), line_control.word_length());
+        assert_eq!(parsed.stop_bits(), line_control.stop_bits());
+        // the const builder sets the same bits as the generated setters
+        let built = LineControlRegister::builder()
+            .word_length(line_control.word_length())
+            .parity(parity)
+            .stop_bits(line_control.stop_bits())
+            .fifos(line_control.enable_fifos())
+            .build();
+        assert_eq!(
+            built.bits(),
+            line_control
+                .with_parity(parity)
+                .with_send_break(false)
+                .bits()
+                & LineControlRegister::USED_BITS
+        );
+    }
+});
@@ -0,0 +1,221 @@
+//! Zero-sized marker types describing what kind of register access a handle allows,
+//! and [`Register`], a handle to a single register that only has the methods its access allows.
+use crate::{BaseAddress, offsets, registrers};
+use core::marker::PhantomData;
+
+/// Marker for a handle that can only read registers.
+#[derive(Debug, Clone, Copy)]
+pub struct ReadOnly;
+
+/// Marker for a handle that can read and write registers.
+#[derive(Debug, Clone, Copy)]
+pub struct ReadWrite;
+
+/// Marker for a register that can only be written.
+#[derive(Debug, Clone, Copy)]
+pub struct WriteOnly;
+
+/// Marker for a register that is the end of a FIFO, which can be read and written but not updated.
+///
+/// A read pops a character from one FIFO and a write pushes one onto another, so a read-modify-write would consume
+/// a received character and send it back out:
+///
+/// ```compile_fail
+/// # use pl011_uart_registers::UART;
+/// let uart = UART::new(0x0900_0000_usize);
+/// uart.registers().data.update(|data| data);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct FifoPort;
+
+/// Access markers that allow reading.
+pub trait Readable {}
+impl Readable for ReadOnly {}
+impl Readable for ReadWrite {}
+impl Readable for FifoPort {}
+
+/// Access markers that allow writing.
+pub trait Writable {}
+impl Writable for WriteOnly {}
+impl Writable for ReadWrite {}
+impl Writable for FifoPort {}
+
+/// A single register of type `R` at `OFFSET` from the base address, with access `A`.
+///
+/// `read` is only available for [`Readable`] access, `write` only for [`Writable`] access and `update` only for
+/// [`ReadWrite`], so e.g. writing the flag register is a compile error rather than a silently ignored bus write,
+/// and so is updating one of the [`FifoPort`] registers.
+/// These are obtained through [`UART::registers`](crate::UART::registers), which guarantees the offsets and types match.
+#[derive(Debug, Clone, Copy)]
+pub struct Register<T: BaseAddress, R, A, const OFFSET: usize> {
+    base: T,
+    _register: PhantomData<(R, A)>,
+}
+
+impl<T: BaseAddress, R, A, const OFFSET: usize> Register<T, R, A, OFFSET> {
+    const fn new(base: T) -> Self {
+        Register {
+            base,
+            _register: PhantomData,
+        }
+    }
+
+    fn address(&self) -> usize {
+        self.base.register_address(OFFSET)
+    }
+}
+
+impl<T: BaseAddress, R, A: Readable, const OFFSET: usize> Register<T, R, A, OFFSET> {
+    pub fn read(&self) -> R {
+        // Safety: only constructed by `Registers::new` with the offset and type of a real register
+        unsafe { self.base.read(self.address()) }
+    }
+}
+
+impl<T: BaseAddress, R, A: Writable, const OFFSET: usize> Register<T, R, A, OFFSET> {
+    pub fn write(&self, value: R) {
+        // Safety: only constructed by `Registers::new` with the offset and type of a real register
+        unsafe { self.base.write(self.address(), value) }
+    }
+}
+
+impl<T: BaseAddress, R, const OFFSET: usize> Register<T, R, ReadWrite, OFFSET> {
+    /// Read-modify-write of the register: reads it, passes the value to `f` and writes back the result.
+    pub fn update(&self, f: impl FnOnce(R) -> R) {
+        self.write(f(self.read()));
+    }
+}
+
+/// Every register of the PL011 as a field, each with only the accesses the TRM allows.
+///
+/// ```ignore
+/// let regs = uart.registers();
+/// let flags = regs.flag.read();
+/// regs.flag.write(flags); // doesn't compile, the flag register is read-only
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Registers<T: BaseAddress> {
+    /// Reading this pops the receive FIFO, see [`UART::read_data_register`](crate::UART::read_data_register).
+    pub data: Register<T, registrers::DataRegister, FifoPort, { offsets::DATA }>,
+    pub receive_status:
+        Register<T, registrers::ReceiveStatusRegister, ReadOnly, { offsets::RECEIVE_STATUS }>,
+    pub error_clear:
+        Register<T, registrers::ErrorClearRegister, WriteOnly, { offsets::ERROR_CLEAR }>,
+    pub flag: Register<T, registrers::FlagRegister, ReadOnly, { offsets::FLAG }>,
+    pub irda_low_power:
+        Register<T, registrers::IrDALowPowerRegister, ReadWrite, { offsets::IRDA_LOW_POWER }>,
+    pub integer_baud_rate_divisor: Register<
+        T,
+        registrers::IntegerBaudRateDivisorRegister,
+        ReadWrite,
+        { offsets::INTEGER_BAUD_RATE_DIVISOR },
+    >,
+    pub fractional_baud_rate_divisor: Register<
+        T,
+        registrers::FractionalBaudRateDivisorRegister,
+        ReadWrite,
+        { offsets::FRACTIONAL_BAUD_RATE_DIVISOR },
+    >,
+    pub line_control:
+        Register<T, registrers::LineControlRegister, ReadWrite, { offsets::LINE_CONTROL }>,
+    pub control: Register<T, registrers::ControlRegister, ReadWrite, { offsets::CONTROL }>,
+    pub interrupt_fifo_level_select: Register<
+        T,
+        registrers::InterruptFIFOLevelSelectRegister,
+        ReadWrite,
+        { offsets::INTERRUPT_FIFO_LEVEL_SELECT },
+    >,
+    pub interrupt_mask_set_clear: Register<
+        T,
+        registrers::InterruptMaskSetClearRegister,
+        ReadWrite,
+        { offsets::INTERRUPT_MASK_SET_CLEAR },
+    >,
+    pub raw_interrupt_status: Register<
+        T,
+        registrers::RawInterruptStatusRegister,
+        ReadOnly,
+        { offsets::RAW_INTERRUPT_STATUS },
+    >,
+    pub masked_interrupt_status: Register<
+        T,
+        registrers::MaskedInterruptStatusRegister,
+        ReadOnly,
+        { offsets::MASKED_INTERRUPT_STATUS },
+    >,
+    pub interrupt_clear:
+        Register<T, registrers::InterruptClearRegister, WriteOnly, { offsets::INTERRUPT_CLEAR }>,
+    pub dma_control:
+        Register<T, registrers::DMAControlRegister, ReadWrite, { offsets::DMA_CONTROL }>,
+    pub test_control:
+        Register<T, registrers::TestControlRegister, ReadWrite, { offsets::TEST_CONTROL }>,
+    pub integration_test_input: Register<
+        T,
+        registrers::IntegrationTestInputRegister,
+        ReadOnly,
+        { offsets::INTEGRATION_TEST_INPUT },
+    >,
+    pub integration_test_output: Register<
+        T,
+        registrers::IntegrationTestOutputRegister,
+        ReadWrite,
+        { offsets::INTEGRATION_TEST_OUTPUT },
+    >,
+    /// In test mode, writing this pushes onto the receive FIFO and reading pops the transmit FIFO.
+    pub integration_test_data: Register<
+        T,
+        registrers::IntegrationTestDataRegister,
+        FifoPort,
+        { offsets::INTEGRATION_TEST_DATA },
+    >,
+    pub peripheral_id_0:
+        Register<T, registrers::PeripheralId0Register, ReadOnly, { offsets::PERIPHERAL_ID_0 }>,
+    pub peripheral_id_1:
+        Register<T, registrers::PeripheralId1Register, ReadOnly, { offsets::PERIPHERAL_ID_1 }>,
+    pub peripheral_id_2:
+        Register<T, registrers::PeripheralId2Register, ReadOnly, { offsets::PERIPHERAL_ID_2 }>,
+    pub peripheral_id_3:
+        Register<T, registrers::PeripheralId3Register, ReadOnly, { offsets::PERIPHERAL_ID_3 }>,
+    pub primecell_id_0:
+        Register<T, registrers::PrimeCellId0Register, ReadOnly, { offsets::PRIMECELL_ID_0 }>,
+    pub primecell_id_1:
+        Register<T, registrers::PrimeCellId1Register, ReadOnly, { offsets::PRIMECELL_ID_1 }>,
+    pub primecell_id_2:
+        Register<T, registrers::PrimeCellId2Register, ReadOnly, { offsets::PRIMECELL_ID_2 }>,
+    pub primecell_id_3:
+        Register<T, registrers::PrimeCellId3Register, ReadOnly, { offsets::PRIMECELL_ID_3 }>,
+}
+
+impl<T: BaseAddress> Registers<T> {
+    pub(crate) const fn new(base: T) -> Self {
+        Registers {
+            data: Register::new(base),
+            receive_status: Register::new(base),
+            error_clear: Register::new(base),
+            flag: Register::new(base),
+            irda_low_power: Register::new(base),
+            integer_baud_rate_divisor: Register::new(base),
+            fractional_baud_rate_divisor: Register::new(base),
+            line_control: Register::new(base),
+            control: Register::new(base),
+            interrupt_fifo_level_select: Register::new(base),
+            interrupt_mask_set_clear: Register::new(base),
+            raw_interrupt_status: Register::new(base),
+            masked_interrupt_status: Register::new(base),
+            interrupt_clear: Register::new(base),
+            dma_control: Register::new(base),
+            test_control: Register::new(base),
+            integration_test_input: Register::new(base),
+            integration_test_output: Register::new(base),
+            integration_test_data: Register::new(base),
+            peripheral_id_0: Register::new(base),
+            peripheral_id_1: Register::new(base),
+            peripheral_id_2: Register::new(base),
+            peripheral_id_3: Register::new(base),
+            primecell_id_0: Register::new(base),
+            primecell_id_1: Register::new(base),
+            primecell_id_2: Register::new(base),
+            primecell_id_3: Register::new(base),
+        }
+    }
+}
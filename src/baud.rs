@@ -0,0 +1,277 @@
+//! Baud rate divisor helpers.
+use bitstuff::ints::u6;
+
+use crate::{
+    BaseAddress, UART,
+    registrers::{FractionalBaudRateDivisorRegister, IntegerBaudRateDivisorRegister},
+};
+
+/// How long the receive FIFO has to hold data without a new character arriving before the receive timeout interrupt
+/// fires, in bit periods.
+///
+/// The TRM fixes this at 32 bit periods, about three 8N1 characters, and it can't be configured. A bit period is one
+/// period of Baud16 divided by 16, so it follows the programmed divisor, see [`BaudRateDivisor::rx_timeout_ns`].
+pub const RX_TIMEOUT_BIT_PERIODS: u32 = 32;
+
+/// Why no valid divisor exists for a clock and baud rate pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BaudError {
+    /// The requested baud rate is zero.
+    ZeroBaudRate,
+    /// The baud rate is too high for the clock, the divisor would be below 1.
+    TooFast,
+    /// The baud rate is too low for the clock, the divisor would be above 65535.
+    TooSlow,
+}
+
+/// A baud rate divisor, split into the values of the integer and fractional divisor registers.
+///
+/// The divisor is `clock / (16 * baud rate)`, with the fraction stored in 64ths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BaudRateDivisor {
+    integer: u16,
+    fractional: u8,
+}
+
+impl BaudRateDivisor {
+    /// Computes the divisor for `baud_rate` from a `clock_hz` UARTCLK, rounding the fraction to the nearest 64th.
+    ///
+    /// This is a `const fn`, see [`baud_divisors_const!`](crate::baud_divisors_const) to do this at compile time.
+    pub const fn from_clock(clock_hz: u32, baud_rate: u32) -> Result<Self, BaudError> {
+        if baud_rate == 0 {
+            return Err(BaudError::ZeroBaudRate);
+        }
+        // the divisor in 64ths is clock * 64 / (16 * baud) = clock * 4 / baud,
+        // calculated in 128ths and halved, rounding up, to round to the nearest 64th
+        let scaled = (clock_hz as u64 * 8 / baud_rate as u64).div_ceil(2);
+        let integer = scaled >> 6;
+        let fractional = (scaled & 0x3F) as u8;
+        if integer == 0 {
+            Err(BaudError::TooFast)
+        } else if integer > 0xFFFF || (integer == 0xFFFF && fractional != 0) {
+            Err(BaudError::TooSlow)
+        } else {
+            Ok(BaudRateDivisor {
+                integer: integer as u16,
+                fractional,
+            })
+        }
+    }
+
+    /// Builds a divisor from the values of the two divisor registers, checking that they are a valid pair.
+    ///
+    /// An `integer` of zero is invalid and gives [`BaudError::TooFast`]. A non-zero `fractional` on top of an `integer`
+    /// of 0xFFFF aborts any transmission or reception and gives [`BaudError::TooSlow`].
+    ///
+    /// ```
+    /// use bitstuff::ints::u6;
+    /// use pl011_uart_registers::baud::{BaudError, BaudRateDivisor};
+    ///
+    /// assert_eq!(BaudRateDivisor::new(0, u6::new(0)), Err(BaudError::TooFast));
+    /// assert_eq!(BaudRateDivisor::new(0, u6::new(5)), Err(BaudError::TooFast));
+    /// assert_eq!(BaudRateDivisor::new(0xFFFF, u6::new(1)), Err(BaudError::TooSlow));
+    /// assert!(BaudRateDivisor::new(0xFFFF, u6::new(0)).is_ok());
+    ///
+    /// let divisor = BaudRateDivisor::new(13, u6::new(1)).unwrap();
+    /// assert_eq!((divisor.integer(), divisor.fractional()), (13, 1));
+    /// ```
+    pub fn new(integer: u16, fractional: u6) -> Result<Self, BaudError> {
+        let fractional = u8::from(fractional);
+        if integer == 0 {
+            Err(BaudError::TooFast)
+        } else if integer == 0xFFFF && fractional != 0 {
+            Err(BaudError::TooSlow)
+        } else {
+            Ok(BaudRateDivisor {
+                integer,
+                fractional,
+            })
+        }
+    }
+
+    /// Converts a divisor given as a number, rounding the fraction to the nearest 64th.
+    ///
+    /// Values outside of the valid range are clamped rather than rejected: anything below 1 (including NaN)
+    /// becomes 1, and anything from 65535 up becomes exactly 65535, as the fractional part has to be zero there.
+    pub const fn from_f64(divisor: f64) -> Self {
+        // `as` saturates, and turns NaN into 0
+        let scaled = (divisor * 64.0 + 0.5) as u32;
+        let scaled = if scaled < 1 << 6 {
+            1 << 6
+        } else if scaled > 0xFFFF << 6 {
+            0xFFFF << 6
+        } else {
+            scaled
+        };
+        BaudRateDivisor {
+            integer: (scaled >> 6) as u16,
+            fractional: (scaled & 0x3F) as u8,
+        }
+    }
+
+    /// The divisor as a number, `integer + fractional / 64`.
+    pub const fn as_f64(self) -> f64 {
+        self.integer as f64 + self.fractional as f64 / 64.0
+    }
+
+    /// The integer part of the divisor, between 1 and 65535.
+    pub const fn integer(self) -> u16 {
+        self.integer
+    }
+
+    /// The fractional part of the divisor in 64ths.
+    pub const fn fractional(self) -> u8 {
+        self.fractional
+    }
+
+    /// The baud rate this divisor actually produces from a `clock_hz` UARTCLK, rounded down.
+    pub const fn baud_rate(self, clock_hz: u32) -> u32 {
+        (clock_hz as u64 * 4 / ((self.integer as u64) << 6 | self.fractional as u64)) as u32
+    }
+
+    /// The receive timeout from a `clock_hz` UARTCLK, in nanoseconds rounded to the nearest one.
+    ///
+    /// This is [`RX_TIMEOUT_BIT_PERIODS`] bit periods at the baud rate this divisor actually produces:
+    ///
+    /// ```
+    /// use pl011_uart_registers::baud::BaudRateDivisor;
+    ///
+    /// // 115200 baud from 24 MHz is really 115246 baud, so 32 bits take 277.667 µs
+    /// let divisor = BaudRateDivisor::from_clock(24_000_000, 115_200).unwrap();
+    /// assert_eq!(divisor.rx_timeout_ns(24_000_000), 277_667);
+    /// let divisor = BaudRateDivisor::from_clock(3_686_400, 9_600).unwrap();
+    /// assert_eq!(divisor.rx_timeout_ns(3_686_400), 3_333_333);
+    /// ```
+    ///
+    /// Panics if `clock_hz` is zero.
+    pub const fn rx_timeout_ns(self, clock_hz: u32) -> u64 {
+        // a bit period is 16 * divisor / clock, with the divisor in 64ths
+        let divisor = (self.integer as u64) << 6 | self.fractional as u64;
+        let numerator = RX_TIMEOUT_BIT_PERIODS as u64 * 16 * divisor * 1_000_000_000;
+        let denominator = 64 * clock_hz as u64;
+        (numerator + denominator / 2) / denominator
+    }
+
+    pub const fn integer_register(self) -> IntegerBaudRateDivisorRegister {
+        IntegerBaudRateDivisorRegister::from_bits(self.integer)
+    }
+
+    pub const fn fractional_register(self) -> FractionalBaudRateDivisorRegister {
+        FractionalBaudRateDivisorRegister::from_bits(self.fractional)
+    }
+
+    /// Writes the integer and then the fractional divisor register of `uart`, see [`UART::set_baud_rate_divisor`].
+    pub fn write_to<T: BaseAddress>(&self, uart: &UART<T>) {
+        uart.set_baud_rate_divisor(*self);
+    }
+}
+
+/// Computes a [`BaudRateDivisor`] at compile time, failing the build if the baud rate can't be reached from the clock.
+///
+/// Both arguments must be constant expressions.
+///
+/// ```ignore
+/// const CONSOLE: BaudRateDivisor = baud_divisors_const!(48_000_000, 115_200);
+/// ```
+#[macro_export]
+macro_rules! baud_divisors_const {
+    ($clock_hz:expr, $baud_rate:expr) => {
+        const {
+            match $crate::baud::BaudRateDivisor::from_clock($clock_hz, $baud_rate) {
+                Ok(divisor) => divisor,
+                Err($crate::baud::BaudError::ZeroBaudRate) => panic!("the baud rate is zero"),
+                Err($crate::baud::BaudError::TooFast) => {
+                    panic!("the baud rate is too high for this clock")
+                }
+                Err($crate::baud::BaudError::TooSlow) => {
+                    panic!("the baud rate is too low for this clock")
+                }
+            }
+        }
+    };
+}
+
+impl<T: BaseAddress> UART<T> {
+    /// Writes both baud rate divisor registers, followed by a write of the line control register with its current value.
+    ///
+    /// The divisors only take effect on a write to the line control register, which is why it gets rewritten here.
+    /// The UART should be disabled while changing the baud rate, after a [`UART::flush`] if anything was sent.
+    pub fn set_baud_rate_divisor(&self, divisor: BaudRateDivisor) {
+        self.write_integer_baud_rate_divisor_register(divisor.integer_register());
+        self.write_fractional_baud_rate_divisor_register(divisor.fractional_register());
+        self.update_line_control_register(|lcr| lcr);
+    }
+
+    /// Computes the divisor for `baud_rate` from a `uart_clk_hz` UARTCLK and writes it, see [`BaudRateDivisor::from_clock`].
+    ///
+    /// Nothing is written if the baud rate can't be reached. E.g. 115200 baud from a 24 MHz clock gives a divisor
+    /// of 13 + 1/64, for an actual rate of 115246 baud (0.04% fast):
+    ///
+    /// ```
+    /// use pl011_uart_registers::baud::BaudRateDivisor;
+    ///
+    /// let divisor = BaudRateDivisor::from_clock(24_000_000, 115_200).unwrap();
+    /// assert_eq!((divisor.integer(), divisor.fractional()), (13, 1));
+    /// assert_eq!(divisor.baud_rate(24_000_000), 115_246);
+    /// // 9600 baud divides a 24 MHz clock almost exactly
+    /// let divisor = BaudRateDivisor::from_clock(24_000_000, 9_600).unwrap();
+    /// assert_eq!((divisor.integer(), divisor.fractional()), (156, 16));
+    /// assert_eq!(divisor.baud_rate(24_000_000), 9_600);
+    /// ```
+    pub fn set_baud_rate(&self, uart_clk_hz: u32, baud_rate: u32) -> Result<(), BaudError> {
+        self.set_baud_rate_divisor(BaudRateDivisor::from_clock(uart_clk_hz, baud_rate)?);
+        Ok(())
+    }
+
+    /// Writes a divisor given as a number, see [`BaudRateDivisor::from_f64`] for the rounding and clamping.
+    ///
+    /// The clamping keeps the registers out of the states the TRM warns about: an integer divisor of 0 is invalid
+    /// and makes the fractional divisor be ignored, and a non-zero fractional divisor on top of an integer
+    /// divisor of 0xFFFF aborts any transmission or reception.
+    pub fn write_baud_rate_divisor(&self, divisor: f64) {
+        self.set_baud_rate_divisor(BaudRateDivisor::from_f64(divisor));
+    }
+}
+
+impl<T: BaseAddress, A> UART<T, A> {
+    /// Returns whether the integer and fractional baud rate divisors currently programmed form a valid pair.
+    ///
+    /// The TRM requires the integer divisor to be non-zero, and the fractional divisor to be zero
+    /// when the integer divisor is at its maximum of 0xFFFF.
+    /// A bootloader can leave the divisors in a state that violates this, so a driver taking over an
+    /// already configured UART should check this before trusting the existing baud rate.
+    pub fn baud_config_is_consistent(&self) -> bool {
+        self.read_baud_rate_divisor().is_some()
+    }
+
+    /// Reads both divisor registers, returning `None` if they don't form a valid divisor.
+    ///
+    /// Use [`BaudRateDivisor::as_f64`] for the divisor as a number.
+    #[must_use]
+    pub fn read_baud_rate_divisor(&self) -> Option<BaudRateDivisor> {
+        let integer = self.read_integer_baud_rate_divisor_register().bits();
+        // the reserved bits above the 6-bit fraction aren't guaranteed to read as zero
+        let fractional = self.read_fractional_baud_rate_divisor_register().bits()
+            & FractionalBaudRateDivisorRegister::USED_BITS;
+        BaudRateDivisor::new(integer, u6::new(fractional)).ok()
+    }
+
+    /// The baud rate the currently programmed divisors produce from a `uart_clk_hz` UARTCLK, rounded down.
+    ///
+    /// Compare this with the requested rate to get the error. Returns 0 if the divisors aren't a valid pair,
+    /// see [`UART::baud_config_is_consistent`].
+    pub fn effective_baud_rate(&self, uart_clk_hz: u32) -> u32 {
+        self.read_baud_rate_divisor()
+            .map_or(0, |divisor| divisor.baud_rate(uart_clk_hz))
+    }
+
+    /// How long the receive FIFO waits for another character before raising the receive timeout interrupt,
+    /// in nanoseconds, with the currently programmed divisors and a `uart_clk_hz` UARTCLK.
+    ///
+    /// Returns `None` if the divisors aren't a valid pair. See [`BaudRateDivisor::rx_timeout_ns`].
+    pub fn rx_timeout_ns(&self, uart_clk_hz: u32) -> Option<u64> {
+        self.read_baud_rate_divisor()
+            .map(|divisor| divisor.rx_timeout_ns(uart_clk_hz))
+    }
+}
@@ -0,0 +1,103 @@
+//! The whole register window as a single `#[repr(C)]` struct.
+use core::mem::{offset_of, size_of};
+
+use crate::{BaseAddress, UART, registrers::*};
+
+/// The memory layout of the 4 KiB PL011 register window, one `u32` per register slot.
+///
+/// This is meant for tooling that treats the peripheral as one block of memory, e.g. to copy a snapshot of it
+/// or to view it in a debugger. Registers sharing an offset share a field: `receive_status` is also the error clear
+/// register when written. The reserved gaps are included so every field sits at its offset from the TRM.
+///
+/// Any access through a pointer to this struct must be volatile and respect the access mode of each register.
+/// In particular copying the whole block reads the data register, which pops the receive FIFO,
+/// and reads the write-only interrupt clear register.
+#[repr(C)]
+#[derive(Debug)]
+pub struct RegisterBlock {
+    pub data: u32,
+    pub receive_status: u32,
+    _reserved_08: [u32; 4],
+    pub flag: u32,
+    _reserved_1c: u32,
+    pub irda_low_power: u32,
+    pub integer_baud_rate_divisor: u32,
+    pub fractional_baud_rate_divisor: u32,
+    pub line_control: u32,
+    pub control: u32,
+    pub interrupt_fifo_level_select: u32,
+    pub interrupt_mask_set_clear: u32,
+    pub raw_interrupt_status: u32,
+    pub masked_interrupt_status: u32,
+    pub interrupt_clear: u32,
+    pub dma_control: u32,
+    _reserved_4c: [u32; 13],
+    pub test_control: u32,
+    pub integration_test_input: u32,
+    pub integration_test_output: u32,
+    pub integration_test_data: u32,
+    _reserved_90: [u32; 980],
+    pub peripheral_id_0: u32,
+    pub peripheral_id_1: u32,
+    pub peripheral_id_2: u32,
+    pub peripheral_id_3: u32,
+    pub primecell_id_0: u32,
+    pub primecell_id_1: u32,
+    pub primecell_id_2: u32,
+    pub primecell_id_3: u32,
+}
+
+/// Fails the build unless every field is at the offset the accessors on [`UART`] use.
+macro_rules! check_offsets {
+    ($($field:ident: $register:ident),* $(,)?) => {
+        const _: () = {
+            $(
+                assert!(
+                    offset_of!(RegisterBlock, $field) == $register::OFFSET,
+                    concat!("RegisterBlock::", stringify!($field), " is at the wrong offset")
+                );
+            )*
+            assert!(size_of::<RegisterBlock>() == 0x1000);
+        };
+    };
+}
+
+check_offsets! {
+    data: DataRegister,
+    receive_status: ReceiveStatusRegister,
+    receive_status: ErrorClearRegister,
+    flag: FlagRegister,
+    irda_low_power: IrDALowPowerRegister,
+    integer_baud_rate_divisor: IntegerBaudRateDivisorRegister,
+    fractional_baud_rate_divisor: FractionalBaudRateDivisorRegister,
+    line_control: LineControlRegister,
+    control: ControlRegister,
+    interrupt_fifo_level_select: InterruptFIFOLevelSelectRegister,
+    interrupt_mask_set_clear: InterruptMaskSetClearRegister,
+    raw_interrupt_status: RawInterruptStatusRegister,
+    masked_interrupt_status: MaskedInterruptStatusRegister,
+    interrupt_clear: InterruptClearRegister,
+    dma_control: DMAControlRegister,
+    test_control: TestControlRegister,
+    integration_test_input: IntegrationTestInputRegister,
+    integration_test_output: IntegrationTestOutputRegister,
+    integration_test_data: IntegrationTestDataRegister,
+    peripheral_id_0: PeripheralId0Register,
+    peripheral_id_1: PeripheralId1Register,
+    peripheral_id_2: PeripheralId2Register,
+    peripheral_id_3: PeripheralId3Register,
+    primecell_id_0: PrimeCellId0Register,
+    primecell_id_1: PrimeCellId1Register,
+    primecell_id_2: PrimeCellId2Register,
+    primecell_id_3: PrimeCellId3Register,
+}
+
+impl<T: BaseAddress, A> UART<T, A> {
+    /// A pointer to the whole register window as a [`RegisterBlock`].
+    ///
+    /// Like the per-register `*_ptr` methods this bypasses [`MmioAccess`](crate::MmioAccess),
+    /// and dereferencing it is subject to the same rules.
+    pub fn as_block_ptr(&self) -> *const RegisterBlock {
+        self.register_ptr(0)
+    }
+}
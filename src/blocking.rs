@@ -0,0 +1,294 @@
+//! Blocking, polled helpers for moving bytes through the UART.
+//!
+//! These busy-wait on the flag register, so they are meant for consoles, bring-up and other simple drivers.
+use crate::{BaseAddress, UART, fifo::FIFO_DEPTH, registrers::DataRegister};
+use core::fmt;
+
+/// An error flagged alongside a received character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ReceiveError {
+    /// The receive FIFO overflowed and characters were lost.
+    Overrun,
+    /// A break condition was received.
+    Break,
+    /// A character was received with a parity error.
+    Parity,
+    /// A character was received without a valid stop bit.
+    Framing,
+}
+
+impl ReceiveError {
+    /// Decodes the error flags of a received data register value.
+    ///
+    /// If several flags are set the first of overrun, break, parity and framing is returned.
+    /// A break is reported as a break even though it also sets the framing error flag.
+    pub fn from_data(data: &DataRegister) -> Option<Self> {
+        if data.overrun_error() {
+            Some(ReceiveError::Overrun)
+        } else if data.break_error() {
+            Some(ReceiveError::Break)
+        } else if data.parity_error() {
+            Some(ReceiveError::Parity)
+        } else if data.framing_error() {
+            Some(ReceiveError::Framing)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the received character, or the error flagged alongside it.
+    pub fn check(data: DataRegister) -> Result<u8, ReceiveError> {
+        match ReceiveError::from_data(&data) {
+            Some(error) => Err(error),
+            None => Ok(data.data()),
+        }
+    }
+}
+
+impl<T: BaseAddress> UART<T> {
+    /// Blocks until a character has been received and returns it, or the error it was received with.
+    ///
+    /// On an error the character is consumed from the receive FIFO.
+    pub fn read_byte(&self) -> Result<u8, ReceiveError> {
+        while self.read_flag_register().receive_fifo_empty() {}
+        ReceiveError::check(self.read_data_register())
+    }
+
+    /// Returns the next received character, or the error it was received with,
+    /// or `None` without waiting if the receive FIFO is empty.
+    pub fn try_read_byte(&self) -> Option<Result<u8, ReceiveError>> {
+        if self.read_flag_register().receive_fifo_empty() {
+            None
+        } else {
+            Some(ReceiveError::check(self.read_data_register()))
+        }
+    }
+
+    /// Returns an iterator over the characters that are in the receive FIFO right now.
+    ///
+    /// Each step checks the flag register and ends the iteration once the receive FIFO is empty, so this never blocks:
+    ///
+    /// ```ignore
+    /// for byte in uart.drain_rx() {
+    ///     match byte {
+    ///         Ok(byte) => handle(byte),
+    ///         Err(error) => log(error),
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Characters that arrive while iterating are yielded as well.
+    pub fn drain_rx(&self) -> RxDrain<'_, T> {
+        RxDrain { uart: self }
+    }
+}
+
+/// An iterator over the characters in the receive FIFO, see [`UART::drain_rx`].
+#[derive(Debug)]
+pub struct RxDrain<'a, T: BaseAddress> {
+    uart: &'a UART<T>,
+}
+
+impl<T: BaseAddress> Iterator for RxDrain<'_, T> {
+    type Item = Result<u8, ReceiveError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.uart.try_read_byte()
+    }
+}
+
+impl<T: BaseAddress> UART<T> {
+    /// Writes every byte of `data` to the data register, blocking while the transmit FIFO is full.
+    ///
+    /// The flag register is only polled once per burst rather than once per byte. Whenever the transmit FIFO is
+    /// empty a whole FIFO's worth of bytes ([`FIFO_DEPTH`]) is written in one go, and while it is neither empty
+    /// nor full one byte at a time. With the FIFOs disabled in the line control register the UART is in character
+    /// mode, where the FIFO is a single holding register, so every burst is a single byte.
+    ///
+    /// This returns once the last byte has been handed to the UART, not when it has left the wire.
+    /// Use [`UART::flush`] to wait for that.
+    pub fn write_bytes(&self, data: &[u8]) {
+        let burst = if self.fifos_enabled() { FIFO_DEPTH } else { 1 };
+        let mut rest = data;
+        while !rest.is_empty() {
+            let flags = self.read_flag_register();
+            let free = if flags.transmit_fifo_empty() {
+                burst
+            } else if flags.can_transmit() {
+                1
+            } else {
+                continue;
+            };
+            let (chunk, tail) = rest.split_at(free.min(rest.len()));
+            for &byte in chunk {
+                self.write_data_register(DataRegister::default().with_data(byte));
+            }
+            rest = tail;
+        }
+    }
+
+    /// Writes `byte` to the data register, blocking while the transmit FIFO is full.
+    pub fn write_byte(&self, byte: u8) {
+        while self.read_flag_register().transmit_fifo_full() {}
+        self.write_data_register(DataRegister::default().with_data(byte));
+    }
+
+    /// Blocks until the transmit FIFO is empty and the UART has finished shifting out the last character,
+    /// including its stop bits.
+    ///
+    /// An empty transmit FIFO alone doesn't mean the transmission is complete: the last character is still being
+    /// shifted out at that point, and BUSY only clears after its stop bits. Call this before anything that would cut
+    /// that character short, such as powering the UART down or changing the baud rate divisors, which would
+    /// otherwise only take effect once the current character has finished.
+    pub fn flush(&self) {
+        loop {
+            let flags = self.read_flag_register();
+            if flags.is_idle() {
+                break;
+            }
+        }
+    }
+
+    /// Starts or stops sending a break, using a read-modify-write of the line control register.
+    ///
+    /// While the break is on, the transmit line is held LOW once the current character has been sent.
+    /// The TRM requires it to be held for at least two complete frames for the break to be received properly,
+    /// and this doesn't wait for that. Use [`UART::send_break`] for a break of the right length.
+    pub fn set_break(&self, on: bool) {
+        self.update_line_control_register(|lcr| lcr.with_send_break(on));
+    }
+
+    /// Sends a break lasting at least two frames and then returns the line to idle, e.g. before a LIN header.
+    ///
+    /// Everything already written is transmitted first, so the break doesn't cut a character short.
+    /// The break is then held while `wait_frame` is called twice, and cleared again.
+    ///
+    /// `wait_frame` must block for at least one frame time at the current baud rate: the start bit, the data bits,
+    /// the parity bit if enabled and the stop bits. That is 10 bit times for 8N1, about 87 µs at 115200 baud.
+    /// It's a callback so any delay source can be used:
+    ///
+    /// ```ignore
+    /// uart.send_break(|| delay.delay_us(100));
+    /// ```
+    pub fn send_break(&self, mut wait_frame: impl FnMut()) {
+        self.flush();
+        self.set_break(true);
+        wait_frame();
+        wait_frame();
+        self.set_break(false);
+    }
+
+    /// Writes `line` followed by `\r\n` and waits until everything has been transmitted.
+    ///
+    /// Because this flushes, the line is guaranteed to be out of the UART when this returns,
+    /// so it's safe to e.g. reset or power down the system straight after.
+    pub fn write_line(&self, line: &str) {
+        self.write_bytes(line.as_bytes());
+        self.write_bytes(b"\r\n");
+        self.flush();
+    }
+
+    /// Reads one received byte, if there is one, and writes it straight back out.
+    ///
+    /// Returns whether a byte was echoed. The byte is echoed even if it was received with an error,
+    /// blocking while the transmit FIFO is full.
+    pub fn echo_once(&self) -> bool {
+        if self.read_flag_register().receive_fifo_empty() {
+            return false;
+        }
+        let byte = self.read_data_register().data();
+        self.write_bytes(&[byte]);
+        true
+    }
+
+    /// Echoes every received byte back out, forever.
+    ///
+    /// This is the classic bring-up test: once the UART is configured, anything typed into the
+    /// terminal on the other end should show up again.
+    pub fn echo_loop(&self) -> ! {
+        loop {
+            self.echo_once();
+        }
+    }
+}
+
+/// Allows `write!`/`writeln!` straight to the UART.
+///
+/// Each byte waits for room in the transmit FIFO, so this blocks until the whole string has been handed to the UART.
+/// The text is sent as is; wrap the UART in a [`CrLf`] if the terminal needs `\r\n` line endings.
+impl<T: BaseAddress> fmt::Write for UART<T> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write_bytes(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// A [`fmt::Write`] adapter that sends every `\n` as `\r\n`, which is what most serial terminals expect.
+///
+/// Get one with [`CrLf::new`], or use [`UART::crlf_writer`] to borrow the handle instead.
+///
+/// ```ignore
+/// writeln!(CrLf::new(uart), "boot ok").ok();
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct CrLf<T: BaseAddress> {
+    uart: UART<T>,
+}
+
+impl<T: BaseAddress> CrLf<T> {
+    pub const fn new(uart: UART<T>) -> Self {
+        CrLf { uart }
+    }
+
+    /// Returns the wrapped UART.
+    pub fn into_inner(self) -> UART<T> {
+        self.uart
+    }
+}
+
+impl<T: BaseAddress> fmt::Write for CrLf<T> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        write_crlf(&self.uart, s);
+        Ok(())
+    }
+}
+
+/// A [`fmt::Write`] adapter like [`CrLf`] that borrows the UART instead of holding a copy of the handle.
+///
+/// Get one with [`UART::crlf_writer`].
+#[derive(Debug)]
+pub struct CrlfWriter<'a, T: BaseAddress> {
+    uart: &'a UART<T>,
+}
+
+impl<T: BaseAddress> fmt::Write for CrlfWriter<'_, T> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        write_crlf(self.uart, s);
+        Ok(())
+    }
+}
+
+/// Writes `s` with every `\n` sent as `\r\n`.
+fn write_crlf<T: BaseAddress>(uart: &UART<T>, s: &str) {
+    for (i, line) in s.split('\n').enumerate() {
+        if i > 0 {
+            uart.write_bytes(b"\r\n");
+        }
+        uart.write_bytes(line.as_bytes());
+    }
+}
+
+impl<T: BaseAddress> UART<T> {
+    /// Returns a [`CrlfWriter`] borrowing this handle, for `write!`/`writeln!` to a terminal:
+    ///
+    /// ```ignore
+    /// writeln!(uart.crlf_writer(), "temperature: {} C", celsius).ok();
+    /// ```
+    ///
+    /// Only text written through the adapter is translated. Bytes written to the UART directly go out as is,
+    /// and received data is never touched, so a terminal that sends `\r` for the enter key still delivers `\r`.
+    pub const fn crlf_writer(&self) -> CrlfWriter<'_, T> {
+        CrlfWriter { uart: self }
+    }
+}
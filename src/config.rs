@@ -0,0 +1,237 @@
+//! A complete description of the serial format and baud rate, with presets for common setups.
+use crate::{
+    BaseAddress, FixedAddress, UART,
+    baud::{BaudError, BaudRateDivisor},
+    modem::FlowControl,
+    registrers::{
+        ControlRegister, DMAControlRegister, FractionalBaudRateDivisorRegister,
+        IntegerBaudRateDivisorRegister, InterruptClearRegister, InterruptFIFOLevelSelectRegister,
+        InterruptMaskSetClearRegister, IrDALowPowerRegister, LineControlRegister, Parity, StopBits,
+        TestControlRegister, WordLength,
+    },
+};
+
+/// Why [`UART::configure`] couldn't apply a [`UartConfig`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ConfigError {
+    /// The baud rate can't be generated from the clock.
+    Baud(BaudError),
+}
+
+impl From<BaudError> for ConfigError {
+    fn from(error: BaudError) -> Self {
+        ConfigError::Baud(error)
+    }
+}
+
+/// The serial format, baud rate and reference clock of a UART.
+///
+/// Build one with [`UartConfig::new`] and the `with_*` methods, or start from one of the presets:
+///
+/// ```ignore
+/// let config = UartConfig::console_115200_8n1(48_000_000);
+/// let config = UartConfig::new(24_000_000, 57_600).with_stop_bits(StopBits::Two);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UartConfig {
+    /// The frequency of UARTCLK, the reference clock the baud rate is derived from, in Hz.
+    pub clock_hz: u32,
+    /// The baud rate in bits per second.
+    pub baud_rate: u32,
+    /// The number of data bits per frame.
+    pub word_length: WordLength,
+    /// The parity mode.
+    pub parity: Parity,
+    /// The number of stop bits transmitted per frame.
+    pub stop_bits: StopBits,
+    /// Whether the transmit and receive FIFOs are enabled.
+    pub enable_fifos: bool,
+    /// The hardware flow control.
+    pub flow_control: FlowControl,
+}
+
+impl UartConfig {
+    /// 8 data bits, no parity, one stop bit (8N1) at `baud_rate`, with the FIFOs enabled and no flow control.
+    pub const fn new(clock_hz: u32, baud_rate: u32) -> Self {
+        UartConfig {
+            clock_hz,
+            baud_rate,
+            word_length: WordLength::EightBits,
+            parity: Parity::None,
+            stop_bits: StopBits::One,
+            enable_fifos: true,
+            flow_control: FlowControl::None,
+        }
+    }
+
+    /// 115200 baud 8N1, the default for almost every serial console.
+    pub const fn console_115200_8n1(clock_hz: u32) -> Self {
+        Self::new(clock_hz, 115_200)
+    }
+
+    /// 9600 baud 8N1, the NMEA 0183 default used by most GPS receivers.
+    pub const fn gps_9600_8n1(clock_hz: u32) -> Self {
+        Self::new(clock_hz, 9_600)
+    }
+
+    pub const fn with_word_length(mut self, word_length: WordLength) -> Self {
+        self.word_length = word_length;
+        self
+    }
+
+    pub const fn with_parity(mut self, parity: Parity) -> Self {
+        self.parity = parity;
+        self
+    }
+
+    pub const fn with_stop_bits(mut self, stop_bits: StopBits) -> Self {
+        self.stop_bits = stop_bits;
+        self
+    }
+
+    pub const fn with_fifos(mut self, enable_fifos: bool) -> Self {
+        self.enable_fifos = enable_fifos;
+        self
+    }
+
+    pub const fn with_flow_control(mut self, flow_control: FlowControl) -> Self {
+        self.flow_control = flow_control;
+        self
+    }
+
+    /// The line control register value for this format. Break is never set.
+    pub const fn line_control_register(&self) -> LineControlRegister {
+        LineControlRegister::builder()
+            .word_length(self.word_length)
+            .parity(self.parity)
+            .stop_bits(self.stop_bits)
+            .fifos(self.enable_fifos)
+            .build()
+    }
+}
+
+impl<T: BaseAddress> UART<T> {
+    /// Applies `config` and enables the UART with both the transmitter and receiver on.
+    ///
+    /// This follows the sequence from the TRM: if the UART is transmitting, everything already written is sent first
+    /// (see [`UART::flush`]), then the UART is disabled, the FIFOs are flushed by disabling them, any character in
+    /// flight is allowed to finish, and only then are the baud rate and line control reprogrammed.
+    /// The divisors only take effect with the line control register write that follows them.
+    /// Data left in the transmit FIFO of a UART that was already disabled is discarded: BUSY stays set for as long as
+    /// the FIFO holds data, even with the UART disabled, so it can't be waited out.
+    /// Finally the control register is written once, with the flow control settings of `config`,
+    /// the transmitter, receiver and UART enabled, and its other bits such as the modem outputs kept as they were.
+    ///
+    /// If the baud rate can't be generated from the clock nothing is written:
+    ///
+    /// ```
+    /// use pl011_uart_registers::{baud::BaudError, config::{ConfigError, UartConfig}, UART};
+    ///
+    /// let uart = UART::new(0x0900_0000_usize);
+    /// let config = UartConfig::new(1_000_000, 115_200);
+    /// assert_eq!(uart.configure(&config), Err(ConfigError::Baud(BaudError::TooFast)));
+    /// ```
+    pub fn configure(&self, config: &UartConfig) -> Result<(), ConfigError> {
+        let divisor = BaudRateDivisor::from_clock(config.clock_hz, config.baud_rate)?;
+        self.flush_if_transmitting();
+        let control = self.read_control_register();
+        self.write_control_register(control.with_uart_enable(false));
+        self.update_line_control_register(|lcr| lcr.with_enable_fifos(false));
+        // with the FIFOs flushed BUSY only covers the character in the shift register
+        while self.read_flag_register().uart_busy() {}
+        // the divisors are latched by the line control register write that follows
+        self.write_integer_baud_rate_divisor_register(divisor.integer_register());
+        self.write_fractional_baud_rate_divisor_register(divisor.fractional_register());
+        self.write_line_control_register(config.line_control_register());
+        self.write_control_register(
+            control
+                .with_flow_control(config.flow_control)
+                .with_transmit_enable(true)
+                .with_receive_enable(true)
+                .with_uart_enable(true),
+        );
+        Ok(())
+    }
+
+    /// Waits until everything written has been sent, if the UART is enabled with the transmitter on.
+    ///
+    /// Otherwise the transmit FIFO doesn't drain, so waiting for it would never end.
+    fn flush_if_transmitting(&self) {
+        let control = self.read_control_register();
+        if control.uart_enable() && control.transmit_enable() {
+            self.flush();
+        }
+    }
+
+    /// Applies `f` to the line control register while the UART is disabled, as the TRM requires.
+    ///
+    /// The order is: if the UART is enabled with the transmitter on, everything already written is sent
+    /// (see [`UART::flush`]), the UART is disabled through the control register, the line control register is
+    /// updated, and finally the control register is written back, re-enabling the UART if it was enabled before.
+    ///
+    /// Waiting for the transmit FIFO to drain while the UART is still enabled also waits out the character in flight.
+    /// BUSY can't be used for that once the UART is disabled, as it stays set for as long as the FIFO holds data.
+    /// If the UART was already disabled, or its transmitter was off, anything waiting in the transmit FIFO stays there,
+    /// and clearing `enable_fifos` in `f` discards it along with the contents of the receive FIFO.
+    /// With hardware flow control this blocks for as long as the other side holds CTS deasserted.
+    pub fn reconfigure_line_control<F>(&self, f: F)
+    where
+        F: FnOnce(LineControlRegister) -> LineControlRegister,
+    {
+        self.flush_if_transmitting();
+        let control = self.read_control_register();
+        self.write_control_register(control.with_uart_enable(false));
+        self.update_line_control_register(f);
+        self.write_control_register(control);
+    }
+
+    /// Writes the power-on reset value to every writable register, e.g. to start from a known state after a bootloader.
+    ///
+    /// The UART is disabled first and the line control register is reset, which flushes the FIFOs, discarding anything
+    /// left in the transmit FIFO. Only then is the character in flight allowed to finish: BUSY stays set for as long as
+    /// the transmit FIFO holds data, even with the UART disabled, so it can't be waited on before the flush.
+    /// The interrupt mask, DMA and test settings are cleared along with any pending interrupts and errors,
+    /// and the control register is written last, which leaves the UART disabled with transmit and receive enabled.
+    /// The integration test registers are only used in test mode and are left as they are.
+    pub fn reset(&self) {
+        self.update_control_register(|cr| cr.with_uart_enable(false));
+        self.write_integer_baud_rate_divisor_register(IntegerBaudRateDivisorRegister::RESET);
+        self.write_fractional_baud_rate_divisor_register(FractionalBaudRateDivisorRegister::RESET);
+        // also latches the divisors and flushes the FIFOs
+        self.write_line_control_register(LineControlRegister::RESET);
+        while self.read_flag_register().uart_busy() {}
+        self.write_interrupt_mask_set_clear_register(InterruptMaskSetClearRegister::RESET);
+        self.write_dma_control_register(DMAControlRegister::RESET);
+        self.write_interrupt_fifo_level_select_register(InterruptFIFOLevelSelectRegister::RESET);
+        self.write_irda_low_power_register(IrDALowPowerRegister::RESET);
+        self.write_test_control_register(TestControlRegister::RESET);
+        self.write_interrupt_clear_register(InterruptClearRegister::clear_all());
+        self.write_error_clear_register();
+        self.write_control_register(ControlRegister::RESET);
+    }
+}
+
+/// Sets up the PL011 at `BASE` as an 8N1 console at `baud_rate` with the FIFOs enabled, in one call.
+///
+/// `clock_hz` is the frequency of UARTCLK. Output still queued by earlier software, e.g. a bootloader, is sent
+/// first. The control register is then reset, so any flow control or loopback left enabled is turned off.
+///
+/// ```ignore
+/// let console = init_console::<0x0900_0000>(24_000_000, 115_200)?;
+/// console.write_line("hello");
+/// ```
+pub fn init_console<const BASE: usize>(
+    clock_hz: u32,
+    baud_rate: u32,
+) -> Result<UART<FixedAddress<BASE>>, ConfigError> {
+    let config = UartConfig::new(clock_hz, baud_rate);
+    // check the baud rate before touching the hardware
+    BaudRateDivisor::from_clock(clock_hz, baud_rate)?;
+    let uart = UART::new(FixedAddress::<BASE>);
+    // resetting the control register disables the UART, which would strand the queued output
+    uart.flush_if_transmitting();
+    uart.write_control_register(ControlRegister::RESET);
+    uart.configure(&config)?;
+    Ok(uart)
+}
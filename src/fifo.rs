@@ -0,0 +1,217 @@
+//! Helpers for turning the FIFOs on and off, and for picking the FIFO interrupt trigger levels by byte count.
+//!
+//! The interrupt FIFO level select register only supports fractions of the FIFO depth,
+//! so a byte count has to be a multiple of an eighth of the FIFO to be representable.
+//! The helpers here check that at compile time rather than silently rounding.
+use crate::{
+    BaseAddress, UART,
+    registrers::{FIFOLevelSelect, InterruptFIFOLevelSelectRegister},
+};
+
+/// The depth of the transmit and receive FIFOs on the PL011 revisions up to r1p4.
+///
+/// Revision r1p5 increased the depth to 32 entries. 16 is a safe lower bound for all revisions.
+pub const FIFO_DEPTH: usize = 16;
+
+/// Returns the trigger level that fires at exactly `bytes` entries of a `depth` entry FIFO, if there is one.
+pub const fn level_for_bytes(depth: usize, bytes: usize) -> Option<FIFOLevelSelect> {
+    if depth == 0 || !depth.is_multiple_of(8) {
+        return None;
+    }
+    let eighth = depth / 8;
+    if bytes == eighth {
+        Some(FIFOLevelSelect::OneEighth)
+    } else if bytes == 2 * eighth {
+        Some(FIFOLevelSelect::OneFourth)
+    } else if bytes == 4 * eighth {
+        Some(FIFOLevelSelect::OneHalf)
+    } else if bytes == 6 * eighth {
+        Some(FIFOLevelSelect::ThreeFourth)
+    } else if bytes == 7 * eighth {
+        Some(FIFOLevelSelect::SevenEighth)
+    } else {
+        None
+    }
+}
+
+/// A FIFO with `DEPTH` entries, used to turn byte counts into trigger levels at compile time.
+///
+/// ```
+/// # use pl011_uart_registers::{fifo::Fifo, registrers::FIFOLevelSelect};
+/// // fires when the receive FIFO holds 4 or more bytes
+/// assert!(matches!(Fifo::<16>::trigger_level::<4>(), FIFOLevelSelect::OneFourth));
+/// ```
+///
+/// A byte count without a level fails the build, with an error that names the nearest valid count:
+///
+/// ```compile_fail
+/// # use pl011_uart_registers::fifo::Fifo;
+/// // no FIFO trigger level matches 5 bytes of a 16 entry FIFO, the nearest is 4 bytes (OneFourth);
+/// // the valid counts are 2, 4, 8, 12 and 14
+/// let level = Fifo::<16>::trigger_level::<5>();
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Fifo<const DEPTH: usize>;
+
+impl<const DEPTH: usize> Fifo<DEPTH> {
+    /// Returns the trigger level for `BYTES` entries, failing the build if no level matches exactly.
+    pub const fn trigger_level<const BYTES: usize>() -> FIFOLevelSelect {
+        const {
+            match level_for_bytes(DEPTH, BYTES) {
+                Some(level) => level,
+                None => panic!("{}", no_level_message(DEPTH, BYTES).as_str()),
+            }
+        }
+    }
+}
+
+/// The compile error of [`Fifo::trigger_level`]: the byte counts that do have a level, and the nearest one.
+const fn no_level_message(depth: usize, bytes: usize) -> Message {
+    if depth == 0 || !depth.is_multiple_of(8) {
+        return Message::new()
+            .push("the FIFO depth has to be a non-zero multiple of 8 to have trigger levels, not ")
+            .push_usize(depth);
+    }
+    let mut nearest = LEVELS[0];
+    let mut valid = Message::new();
+    let mut i = 0;
+    while i < LEVELS.len() {
+        let count = depth / 8 * LEVELS[i].0;
+        if count.abs_diff(bytes) < (depth / 8 * nearest.0).abs_diff(bytes) {
+            nearest = LEVELS[i];
+        }
+        valid = match i {
+            0 => valid,
+            _ if i == LEVELS.len() - 1 => valid.push(" and "),
+            _ => valid.push(", "),
+        }
+        .push_usize(count);
+        i += 1;
+    }
+    Message::new()
+        .push("no FIFO trigger level matches ")
+        .push_usize(bytes)
+        .push(" bytes of a ")
+        .push_usize(depth)
+        .push(" entry FIFO, the nearest is ")
+        .push_usize(depth / 8 * nearest.0)
+        .push(" bytes (")
+        .push(nearest.1)
+        .push("); the valid counts are ")
+        .push(valid.as_str())
+}
+
+/// The trigger levels in eighths of the FIFO depth, with the name of their [`FIFOLevelSelect`] variant.
+const LEVELS: [(usize, &str); 5] = [
+    (1, "OneEighth"),
+    (2, "OneFourth"),
+    (4, "OneHalf"),
+    (6, "ThreeFourth"),
+    (7, "SevenEighth"),
+];
+
+/// A string built at compile time, as `format!` isn't available in const contexts. Anything past the capacity is
+/// cut off.
+struct Message {
+    buffer: [u8; 192],
+    len: usize,
+}
+
+impl Message {
+    const fn new() -> Self {
+        Message {
+            buffer: [0; 192],
+            len: 0,
+        }
+    }
+
+    const fn push(mut self, s: &str) -> Self {
+        let bytes = s.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() && self.len < self.buffer.len() {
+            self.buffer[self.len] = bytes[i];
+            self.len += 1;
+            i += 1;
+        }
+        self
+    }
+
+    const fn push_usize(self, n: usize) -> Self {
+        let mut digits = [0; 20];
+        let mut start = digits.len();
+        let mut n = n;
+        loop {
+            start -= 1;
+            digits[start] = b'0' + (n % 10) as u8;
+            n /= 10;
+            if n == 0 {
+                break;
+            }
+        }
+        match core::str::from_utf8(digits.split_at(start).1) {
+            Ok(digits) => self.push(digits),
+            Err(_) => self,
+        }
+    }
+
+    const fn as_str(&self) -> &str {
+        match core::str::from_utf8(self.buffer.split_at(self.len).0) {
+            Ok(s) => s,
+            Err(_) => "",
+        }
+    }
+}
+
+impl<T: BaseAddress> UART<T> {
+    /// Sets the receive and transmit interrupt trigger levels from byte counts for a `DEPTH` entry FIFO.
+    ///
+    /// The receive interrupt fires once the receive FIFO holds `RX` or more bytes,
+    /// the transmit interrupt once the transmit FIFO holds `TX` or fewer bytes.
+    /// Counts that don't map exactly onto a [`FIFOLevelSelect`] are rejected at compile time.
+    pub fn set_fifo_trigger_bytes<const DEPTH: usize, const RX: usize, const TX: usize>(&self) {
+        self.set_fifo_levels(
+            Fifo::<DEPTH>::trigger_level::<RX>(),
+            Fifo::<DEPTH>::trigger_level::<TX>(),
+        );
+    }
+
+    /// Sets the receive and transmit interrupt trigger levels with a single write of the interrupt FIFO level select
+    /// register.
+    ///
+    /// The receive interrupt fires when the receive FIFO fills past `rx`, the transmit interrupt when the transmit
+    /// FIFO drains past `tx`.
+    pub fn set_fifo_levels(&self, rx: FIFOLevelSelect, tx: FIFOLevelSelect) {
+        self.write_interrupt_fifo_level_select_register(InterruptFIFOLevelSelectRegister::new(
+            rx, tx,
+        ));
+    }
+
+    /// Enables or disables the transmit and receive FIFOs.
+    ///
+    /// Changing the FEN bit flushes both FIFOs: anything received but not yet read is lost, so drain the receive FIFO
+    /// first to keep the pending input. Pending output is sent before the change if the UART is enabled with the
+    /// transmitter on, and lost otherwise. The errors of the discarded characters are cleared along with them, so the
+    /// receive status register doesn't report errors for characters that were never read. Nothing is flushed if the
+    /// FIFOs already are in the requested state.
+    ///
+    /// The line control register is changed with the UART disabled, as the TRM requires,
+    /// see [`UART::reconfigure_line_control`]. See [`UART::fifos_enabled`] for how this affects the flag register.
+    pub fn set_fifos_enabled(&self, on: bool) {
+        if self.fifos_enabled() == on {
+            return;
+        }
+        self.reconfigure_line_control(|lcr| lcr.with_enable_fifos(on));
+        self.write_error_clear_register();
+    }
+}
+
+impl<T: BaseAddress, A> UART<T, A> {
+    /// Returns whether the transmit and receive FIFOs are enabled (FEN in the line control register).
+    ///
+    /// This changes what the FIFO bits of the flag register mean. With the FIFOs disabled the UART is in character
+    /// mode, where each FIFO is a single holding register: TXFF and RXFF are set as soon as it holds one character,
+    /// and TXFE and RXFE when it's empty. The same flags refer to the whole 16 or 32 entry FIFOs when they're enabled.
+    pub fn fifos_enabled(&self) -> bool {
+        self.read_line_control_register().enable_fifos()
+    }
+}
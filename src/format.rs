@@ -0,0 +1,370 @@
+//! `defmt::Format` for the register types, printing every field like their `Debug` output.
+//!
+//! Fields that fail to decode are printed as `Err` with their raw value.
+use defmt::{Format, Formatter};
+
+use crate::registrers::*;
+
+impl Format for DataRegister {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "DataRegister {{ overrun_error: {}, break_error: {}, parity_error: {}, framing_error: {}, data: {} }}",
+            self.overrun_error(),
+            self.break_error(),
+            self.parity_error(),
+            self.framing_error(),
+            self.data(),
+        )
+    }
+}
+
+impl Format for ReceiveStatusRegister {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "ReceiveStatusRegister {{ overrun_error: {}, break_error: {}, parity_error: {}, framing_error: {} }}",
+            self.overrun_error(),
+            self.break_error(),
+            self.parity_error(),
+            self.framing_error(),
+        )
+    }
+}
+
+impl Format for FlagRegister {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "FlagRegister {{ ring_indicator: {}, transmit_fifo_empty: {}, receive_fifo_full: {}, transmit_fifo_full: {}, receive_fifo_empty: {}, uart_busy: {}, data_carrier_detect: {}, data_set_ready: {}, clear_to_send: {} }}",
+            self.ring_indicator(),
+            self.transmit_fifo_empty(),
+            self.receive_fifo_full(),
+            self.transmit_fifo_full(),
+            self.receive_fifo_empty(),
+            self.uart_busy(),
+            self.data_carrier_detect(),
+            self.data_set_ready(),
+            self.clear_to_send(),
+        )
+    }
+}
+
+impl Format for IrDALowPowerRegister {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "IrDALowPowerRegister {{ low_power_divisor_value: {} }}",
+            self.low_power_divisor_value(),
+        )
+    }
+}
+
+impl Format for IntegerBaudRateDivisorRegister {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "IntegerBaudRateDivisorRegister {{ integer_baud_rate_divisor: {} }}",
+            self.integer_baud_rate_divisor(),
+        )
+    }
+}
+
+impl Format for FractionalBaudRateDivisorRegister {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "FractionalBaudRateDivisorRegister {{ fractional_baud_rate_divisor: {} }}",
+            u8::from(self.fractional_baud_rate_divisor()),
+        )
+    }
+}
+
+impl Format for LineControlRegister {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "LineControlRegister {{ stick_parity: {}, word_length: {}, enable_fifos: {}, stop_bits: {}, parity_polarity: {}, parity_enable: {}, send_break: {} }}",
+            self.stick_parity(),
+            self.word_length(),
+            self.enable_fifos(),
+            self.stop_bits(),
+            self.parity_polarity(),
+            self.parity_enable(),
+            self.send_break(),
+        )
+    }
+}
+
+impl Format for ControlRegister {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "ControlRegister {{ cts_hardware_flow_control_enable: {}, rts_hardware_flow_control_enable: {}, out2: {}, out1: {}, request_to_send: {}, data_transmit_ready: {}, receive_enable: {}, transmit_enable: {}, loopback_enable: {}, sir_enable: {}, uart_enable: {} }}",
+            self.cts_hardware_flow_control_enable(),
+            self.rts_hardware_flow_control_enable(),
+            self.out2(),
+            self.out1(),
+            self.request_to_send(),
+            self.data_transmit_ready(),
+            self.receive_enable(),
+            self.transmit_enable(),
+            self.loopback_enable(),
+            self.sir_enable(),
+            self.uart_enable(),
+        )
+    }
+}
+
+impl Format for InterruptFIFOLevelSelectRegister {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "InterruptFIFOLevelSelectRegister {{ receive_interrupt_fifo_level_select: {}, transmit_interrupt_fifo_level_select: {} }}",
+            self.receive_interrupt_fifo_level_select(),
+            self.transmit_interrupt_fifo_level_select(),
+        )
+    }
+}
+
+impl Format for InterruptMaskSetClearRegister {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "InterruptMaskSetClearRegister {{ overrun_error_interrupt_mask: {}, break_error_interrupt_mask: {}, parity_error_interrupt_mask: {}, framing_error_interrupt_mask: {}, receive_timeout_interrupt_mask: {}, transmit_interrupt_mask: {}, receive_interrupt_mask: {}, n_uartdsr_modem_interrupt_mask: {}, n_uartdcd_modem_interrupt_mask: {}, n_uartcts_modem_interrupt_mask: {}, n_uartri_modem_interrupt_mask: {} }}",
+            self.overrun_error_interrupt_mask(),
+            self.break_error_interrupt_mask(),
+            self.parity_error_interrupt_mask(),
+            self.framing_error_interrupt_mask(),
+            self.receive_timeout_interrupt_mask(),
+            self.transmit_interrupt_mask(),
+            self.receive_interrupt_mask(),
+            self.n_uartdsr_modem_interrupt_mask(),
+            self.n_uartdcd_modem_interrupt_mask(),
+            self.n_uartcts_modem_interrupt_mask(),
+            self.n_uartri_modem_interrupt_mask(),
+        )
+    }
+}
+
+impl Format for RawInterruptStatusRegister {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "RawInterruptStatusRegister {{ overrun_error_raw_interrupt: {}, break_error_raw_interrupt: {}, parity_error_raw_interrupt: {}, framing_error_raw_interrupt: {}, receive_timeout_raw_interrupt: {}, transmit_raw_interrupt: {}, receive_raw_interrupt: {}, n_uartdsr_modem_raw_interrupt: {}, n_uartdcd_modem_raw_interrupt: {}, n_uartcts_modem_raw_interrupt: {}, n_uartri_modem_raw_interrupt: {} }}",
+            self.overrun_error_raw_interrupt(),
+            self.break_error_raw_interrupt(),
+            self.parity_error_raw_interrupt(),
+            self.framing_error_raw_interrupt(),
+            self.receive_timeout_raw_interrupt(),
+            self.transmit_raw_interrupt(),
+            self.receive_raw_interrupt(),
+            self.n_uartdsr_modem_raw_interrupt(),
+            self.n_uartdcd_modem_raw_interrupt(),
+            self.n_uartcts_modem_raw_interrupt(),
+            self.n_uartri_modem_raw_interrupt(),
+        )
+    }
+}
+
+impl Format for MaskedInterruptStatusRegister {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "MaskedInterruptStatusRegister {{ overrun_error_interrupt_status: {}, break_error_interrupt_status: {}, parity_error_interrupt_status: {}, framing_error_interrupt_status: {}, receive_timeout_interrupt_status: {}, transmit_interrupt_status: {}, receive_interrupt_status: {}, n_uartdsr_modem_interrupt_status: {}, n_uartdcd_modem_interrupt_status: {}, n_uartcts_modem_interrupt_status: {}, n_uartri_modem_interrupt_status: {} }}",
+            self.overrun_error_interrupt_status(),
+            self.break_error_interrupt_status(),
+            self.parity_error_interrupt_status(),
+            self.framing_error_interrupt_status(),
+            self.receive_timeout_interrupt_status(),
+            self.transmit_interrupt_status(),
+            self.receive_interrupt_status(),
+            self.n_uartdsr_modem_interrupt_status(),
+            self.n_uartdcd_modem_interrupt_status(),
+            self.n_uartcts_modem_interrupt_status(),
+            self.n_uartri_modem_interrupt_status(),
+        )
+    }
+}
+
+impl Format for InterruptClearRegister {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "InterruptClearRegister {{ clear_overrun_error_interrupt: {}, clear_break_error_interrupt: {}, clear_parity_error_interrupt: {}, clear_framing_error_interrupt: {}, clear_receive_timeout_interrupt: {}, clear_transmit_interrupt: {}, clear_receive_interrupt: {}, clear_n_uartdsr_modem_interrupt: {}, clear_n_uartdcd_modem_interrupt: {}, clear_n_uartcts_modem_interrupt: {}, clear_n_uartri_modem_interrupt: {} }}",
+            self.clear_overrun_error_interrupt(),
+            self.clear_break_error_interrupt(),
+            self.clear_parity_error_interrupt(),
+            self.clear_framing_error_interrupt(),
+            self.clear_receive_timeout_interrupt(),
+            self.clear_transmit_interrupt(),
+            self.clear_receive_interrupt(),
+            self.clear_n_uartdsr_modem_interrupt(),
+            self.clear_n_uartdcd_modem_interrupt(),
+            self.clear_n_uartcts_modem_interrupt(),
+            self.clear_n_uartri_modem_interrupt(),
+        )
+    }
+}
+
+impl Format for DMAControlRegister {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "DMAControlRegister {{ dma_on_error: {}, transmit_dma_enable: {}, receive_dma_enable: {} }}",
+            self.dma_on_error(),
+            self.transmit_dma_enable(),
+            self.receive_dma_enable(),
+        )
+    }
+}
+
+impl Format for TestControlRegister {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "TestControlRegister {{ sir_test: {}, test_fifo_enable: {}, integration_test_enable: {} }}",
+            self.sir_test(),
+            self.test_fifo_enable(),
+            self.integration_test_enable(),
+        )
+    }
+}
+
+impl Format for IntegrationTestInputRegister {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "IntegrationTestInputRegister {{ n_uartcts: {}, n_uartdcd: {}, n_uartdsr: {}, n_uartri: {}, uart_txdmaclr: {}, uart_rxdmaclr: {}, sirin: {}, uart_rxd: {} }}",
+            self.n_uartcts(),
+            self.n_uartdcd(),
+            self.n_uartdsr(),
+            self.n_uartri(),
+            self.uart_txdmaclr(),
+            self.uart_rxdmaclr(),
+            self.sirin(),
+            self.uart_rxd(),
+        )
+    }
+}
+
+impl Format for IntegrationTestOutputRegister {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "IntegrationTestOutputRegister {{ uart_msintr: {}, uart_rxintr: {}, uart_txintr: {}, uart_rtintr: {}, uart_eintr: {}, uart_intr: {}, n_uartout2: {}, n_uartout1: {}, n_uartrts: {}, n_uartdtr: {}, uart_txdmasreq: {}, uart_txdmabreq: {}, uart_rxdmasreq: {}, uart_rxdmabreq: {}, n_sirout: {}, uart_txd: {} }}",
+            self.uart_msintr(),
+            self.uart_rxintr(),
+            self.uart_txintr(),
+            self.uart_rtintr(),
+            self.uart_eintr(),
+            self.uart_intr(),
+            self.n_uartout2(),
+            self.n_uartout1(),
+            self.n_uartrts(),
+            self.n_uartdtr(),
+            self.uart_txdmasreq(),
+            self.uart_txdmabreq(),
+            self.uart_rxdmasreq(),
+            self.uart_rxdmabreq(),
+            self.n_sirout(),
+            self.uart_txd(),
+        )
+    }
+}
+
+impl Format for IntegrationTestDataRegister {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "IntegrationTestDataRegister {{ overrun_error: {}, break_error: {}, parity_error: {}, framing_error: {}, data: {} }}",
+            self.overrun_error(),
+            self.break_error(),
+            self.parity_error(),
+            self.framing_error(),
+            self.data(),
+        )
+    }
+}
+
+impl Format for PeripheralId0Register {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "PeripheralId0Register {{ part_number_0: {} }}",
+            self.part_number_0(),
+        )
+    }
+}
+
+impl Format for PeripheralId1Register {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "PeripheralId1Register {{ designer_0: {}, part_number_1: {} }}",
+            u8::from(self.designer_0()),
+            u8::from(self.part_number_1()),
+        )
+    }
+}
+
+impl Format for PeripheralId2Register {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "PeripheralId2Register {{ revision: {}, designer_1: {} }}",
+            u8::from(self.revision()),
+            u8::from(self.designer_1()),
+        )
+    }
+}
+
+impl Format for PeripheralId3Register {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "PeripheralId3Register {{ configuration: {} }}",
+            self.configuration(),
+        )
+    }
+}
+
+impl Format for PrimeCellId0Register {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "PrimeCellId0Register {{ primecell_id_0: {} }}",
+            self.primecell_id_0(),
+        )
+    }
+}
+
+impl Format for PrimeCellId1Register {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "PrimeCellId1Register {{ primecell_id_1: {} }}",
+            self.primecell_id_1(),
+        )
+    }
+}
+
+impl Format for PrimeCellId2Register {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "PrimeCellId2Register {{ primecell_id_2: {} }}",
+            self.primecell_id_2(),
+        )
+    }
+}
+
+impl Format for PrimeCellId3Register {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "PrimeCellId3Register {{ primecell_id_3: {} }}",
+            self.primecell_id_3(),
+        )
+    }
+}
@@ -0,0 +1,263 @@
+//! Decoding of the error flags that come with every received character.
+//!
+//! The data register reports the errors of the character it returns in bits 8 to 11, and the receive status register
+//! repeats them in bits 0 to 3. [`FrameError`] holds any combination of them, so none are lost when several are set:
+//!
+//! ```
+//! use pl011_uart_registers::{frame::FrameError, registrers::DataRegister};
+//!
+//! let clean = DataRegister::default().with_data(b'a');
+//! assert_eq!(clean.status(), Ok(b'a'));
+//!
+//! // a break also sets the framing error flag, and the overrun happened on top of it
+//! let received = DataRegister::default()
+//!     .with_break_error(true)
+//!     .with_framing_error(true)
+//!     .with_overrun_error(true);
+//! let errors = received.status().unwrap_err();
+//! assert_eq!(errors, FrameError::BREAK | FrameError::FRAMING | FrameError::OVERRUN);
+//! assert!(errors.contains(FrameError::BREAK) && !errors.contains(FrameError::PARITY));
+//! assert_eq!(format!("{errors:?}"), "FrameError(OVERRUN | BREAK | FRAMING)");
+//! ```
+//!
+//! [`ReceivedWord`] is the same information as plain fields, for code that keeps every character together with its
+//! own error flags, e.g. in a software FIFO, and acts on a parity error of that one character rather than on the
+//! sticky flags of the receive status register:
+//!
+//! ```
+//! use pl011_uart_registers::{frame::ReceivedWord, registrers::DataRegister};
+//!
+//! let word = DataRegister::default().with_data(b'x').with_parity_error(true).received();
+//! assert_eq!(
+//!     word,
+//!     ReceivedWord { data: b'x', overrun_error: false, break_error: false, parity_error: true, framing_error: false }
+//! );
+//! assert!(!word.is_ok());
+//! assert_eq!(DataRegister::from(word).received(), word);
+//! ```
+use core::{fmt, ops};
+
+use crate::{
+    BaseAddress, UART,
+    registrers::{DataRegister, ReceiveStatusRegister},
+};
+
+/// A set of receive errors, laid out like bits 0 to 3 of the receive status register.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FrameError(u8);
+
+impl FrameError {
+    /// Data arrived while the receive FIFO was full and was lost.
+    pub const OVERRUN: Self = Self(1 << 3);
+    /// A break condition was received: the line was held LOW for longer than a full frame.
+    pub const BREAK: Self = Self(1 << 2);
+    /// The parity bit didn't match the parity selected in the line control register.
+    pub const PARITY: Self = Self(1 << 1);
+    /// The character didn't have a valid stop bit.
+    pub const FRAMING: Self = Self(1 << 0);
+
+    /// No errors.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// All four errors.
+    pub const fn all() -> Self {
+        Self(0b1111)
+    }
+
+    /// The raw bits, as they are laid out in the receive status register.
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Builds a set from the raw bits of the receive status register, dropping the reserved bits above bit 3.
+    pub const fn from_bits_truncate(bits: u8) -> Self {
+        Self(bits & Self::all().0)
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns whether every error in `other` is also in `self`.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns whether any error in `other` is also in `self`.
+    pub const fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl ops::BitOr for FrameError {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl ops::BitOrAssign for FrameError {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl ops::BitAnd for FrameError {
+    type Output = Self;
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+/// Lists the set flags, e.g. `FrameError(BREAK | FRAMING)`.
+impl fmt::Debug for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const FLAGS: [(FrameError, &str); 4] = [
+            (FrameError::OVERRUN, "OVERRUN"),
+            (FrameError::BREAK, "BREAK"),
+            (FrameError::PARITY, "PARITY"),
+            (FrameError::FRAMING, "FRAMING"),
+        ];
+        f.write_str("FrameError(")?;
+        let mut first = true;
+        for (flag, name) in FLAGS {
+            if self.contains(flag) {
+                if !first {
+                    f.write_str(" | ")?;
+                }
+                f.write_str(name)?;
+                first = false;
+            }
+        }
+        f.write_str(")")
+    }
+}
+
+impl DataRegister {
+    /// The errors flagged for the received character in this value.
+    pub fn errors(&self) -> FrameError {
+        FrameError::from_bits_truncate((self.bits() >> 8) as u8)
+    }
+
+    /// Returns the received character, or every error flagged alongside it.
+    ///
+    /// Unlike [`ReceiveError::check`](crate::blocking::ReceiveError::check), which picks the most severe error,
+    /// this reports all of them.
+    pub fn status(&self) -> Result<u8, FrameError> {
+        let errors = self.errors();
+        if errors.is_empty() {
+            Ok(self.data())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// The received character together with its error flags, all decoded from this one value.
+    pub fn received(&self) -> ReceivedWord {
+        ReceivedWord {
+            data: self.data(),
+            overrun_error: self.overrun_error(),
+            break_error: self.break_error(),
+            parity_error: self.parity_error(),
+            framing_error: self.framing_error(),
+        }
+    }
+}
+
+impl ReceiveStatusRegister {
+    /// The errors flagged for the last character read from the data register, plus an overrun as soon as it happens.
+    pub fn errors(&self) -> FrameError {
+        FrameError::from_bits_truncate(self.bits() as u8)
+    }
+}
+
+/// A received character with the error flags it came with, see [`DataRegister::received`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ReceivedWord {
+    pub data: u8,
+    /// The receive FIFO was full when this character arrived, so the characters after it were lost.
+    pub overrun_error: bool,
+    /// This character is a break condition, its data is 0.
+    pub break_error: bool,
+    pub parity_error: bool,
+    pub framing_error: bool,
+}
+
+impl ReceivedWord {
+    /// The error flags as a set.
+    pub fn errors(&self) -> FrameError {
+        DataRegister::from(*self).errors()
+    }
+
+    /// Returns whether the character was received without any error.
+    pub fn is_ok(&self) -> bool {
+        self.errors().is_empty()
+    }
+}
+
+impl From<DataRegister> for ReceivedWord {
+    fn from(data: DataRegister) -> Self {
+        data.received()
+    }
+}
+
+/// Packs the word back into the 12 bits the data register returned it in.
+impl From<ReceivedWord> for DataRegister {
+    fn from(word: ReceivedWord) -> Self {
+        DataRegister::default()
+            .with_data(word.data)
+            .with_overrun_error(word.overrun_error)
+            .with_break_error(word.break_error)
+            .with_parity_error(word.parity_error)
+            .with_framing_error(word.framing_error)
+    }
+}
+
+impl<T: BaseAddress> UART<T> {
+    /// Returns the next received character with its error flags, or `None` without waiting if the receive FIFO is
+    /// empty.
+    ///
+    /// The character and its flags come from a single read of the data register, so they always belong together.
+    pub fn try_read_received(&self) -> Option<ReceivedWord> {
+        if self.read_flag_register().receive_fifo_empty() {
+            None
+        } else {
+            Some(self.read_data_register().received())
+        }
+    }
+
+    /// Returns an iterator over the characters that are in the receive FIFO right now, each with its error flags.
+    ///
+    /// Unlike [`UART::drain_rx`] this keeps the character of an erroneous read, and every flag that was set for it:
+    ///
+    /// ```ignore
+    /// for word in uart.drain_received() {
+    ///     if word.parity_error {
+    ///         request_retransmit();
+    ///     } else {
+    ///         software_fifo.push(word);
+    ///     }
+    /// }
+    /// ```
+    pub fn drain_received(&self) -> ReceivedDrain<'_, T> {
+        ReceivedDrain { uart: self }
+    }
+}
+
+/// An iterator over the characters in the receive FIFO and their error flags, see [`UART::drain_received`].
+#[derive(Debug)]
+pub struct ReceivedDrain<'a, T: BaseAddress> {
+    uart: &'a UART<T>,
+}
+
+impl<T: BaseAddress> Iterator for ReceivedDrain<'_, T> {
+    type Item = ReceivedWord;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.uart.try_read_received()
+    }
+}
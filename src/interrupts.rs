@@ -0,0 +1,441 @@
+//! Decoding of the interrupt sources of the UART.
+use core::{fmt, ops};
+
+use crate::{
+    BaseAddress, UART,
+    registrers::{
+        InterruptClearRegister, InterruptMaskSetClearRegister, MaskedInterruptStatusRegister,
+        RawInterruptStatusRegister,
+    },
+};
+
+/// One of the eleven interrupt sources of the UART.
+///
+/// The variants are listed in the order of their bits in the interrupt registers, from bit 10 down to bit 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum InterruptSource {
+    /// Overrun error interrupt, bit 10.
+    Overrun,
+    /// Break error interrupt, bit 9.
+    Break,
+    /// Parity error interrupt, bit 8.
+    Parity,
+    /// Framing error interrupt, bit 7.
+    Framing,
+    /// Receive timeout interrupt, bit 6.
+    ReceiveTimeout,
+    /// Transmit interrupt, bit 5.
+    Transmit,
+    /// Receive interrupt, bit 4.
+    Receive,
+    /// nUARTDSR modem interrupt, bit 3.
+    DataSetReady,
+    /// nUARTDCD modem interrupt, bit 2.
+    DataCarrierDetect,
+    /// nUARTCTS modem interrupt, bit 1.
+    ClearToSend,
+    /// nUARTRI modem interrupt, bit 0.
+    RingIndicator,
+}
+
+impl InterruptSource {
+    /// Every interrupt source, in the order of their bits from high to low.
+    pub const ALL: [InterruptSource; 11] = [
+        InterruptSource::Overrun,
+        InterruptSource::Break,
+        InterruptSource::Parity,
+        InterruptSource::Framing,
+        InterruptSource::ReceiveTimeout,
+        InterruptSource::Transmit,
+        InterruptSource::Receive,
+        InterruptSource::DataSetReady,
+        InterruptSource::DataCarrierDetect,
+        InterruptSource::ClearToSend,
+        InterruptSource::RingIndicator,
+    ];
+
+    /// The bit of this source in [`Interrupts`] and the interrupt registers.
+    pub const fn interrupts(self) -> Interrupts {
+        match self {
+            InterruptSource::Overrun => Interrupts::OVERRUN,
+            InterruptSource::Break => Interrupts::BREAK,
+            InterruptSource::Parity => Interrupts::PARITY,
+            InterruptSource::Framing => Interrupts::FRAMING,
+            InterruptSource::ReceiveTimeout => Interrupts::RX_TIMEOUT,
+            InterruptSource::Transmit => Interrupts::TX,
+            InterruptSource::Receive => Interrupts::RX,
+            InterruptSource::DataSetReady => Interrupts::DSR,
+            InterruptSource::DataCarrierDetect => Interrupts::DCD,
+            InterruptSource::ClearToSend => Interrupts::CTS,
+            InterruptSource::RingIndicator => Interrupts::RI,
+        }
+    }
+
+    /// Returns whether this source is asserted in a masked interrupt status value.
+    pub fn is_pending(self, status: &MaskedInterruptStatusRegister) -> bool {
+        match self {
+            InterruptSource::Overrun => status.overrun_error_interrupt_status(),
+            InterruptSource::Break => status.break_error_interrupt_status(),
+            InterruptSource::Parity => status.parity_error_interrupt_status(),
+            InterruptSource::Framing => status.framing_error_interrupt_status(),
+            InterruptSource::ReceiveTimeout => status.receive_timeout_interrupt_status(),
+            InterruptSource::Transmit => status.transmit_interrupt_status(),
+            InterruptSource::Receive => status.receive_interrupt_status(),
+            InterruptSource::DataSetReady => status.n_uartdsr_modem_interrupt_status(),
+            InterruptSource::DataCarrierDetect => status.n_uartdcd_modem_interrupt_status(),
+            InterruptSource::ClearToSend => status.n_uartcts_modem_interrupt_status(),
+            InterruptSource::RingIndicator => status.n_uartri_modem_interrupt_status(),
+        }
+    }
+}
+
+/// A set of interrupt sources, in the bit layout shared by the interrupt mask, raw status, masked status and clear registers.
+///
+/// Combine the flags with `|` and convert to and from any of those registers:
+///
+/// ```ignore
+/// let pending = uart.read_masked_interrupt_status_register().interrupts();
+/// if pending.intersects(Interrupts::RX | Interrupts::RX_TIMEOUT) {
+///     // drain the receive FIFO
+/// }
+/// uart.write_interrupt_clear_register((Interrupts::RX | Interrupts::RX_TIMEOUT).into());
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Interrupts(u16);
+
+impl Interrupts {
+    /// Overrun error interrupt, bit 10.
+    pub const OVERRUN: Self = Self(1 << 10);
+    /// Break error interrupt, bit 9.
+    pub const BREAK: Self = Self(1 << 9);
+    /// Parity error interrupt, bit 8.
+    pub const PARITY: Self = Self(1 << 8);
+    /// Framing error interrupt, bit 7.
+    pub const FRAMING: Self = Self(1 << 7);
+    /// Receive timeout interrupt, bit 6.
+    pub const RX_TIMEOUT: Self = Self(1 << 6);
+    /// Transmit interrupt, bit 5.
+    pub const TX: Self = Self(1 << 5);
+    /// Receive interrupt, bit 4.
+    pub const RX: Self = Self(1 << 4);
+    /// nUARTDSR modem interrupt, bit 3.
+    pub const DSR: Self = Self(1 << 3);
+    /// nUARTDCD modem interrupt, bit 2.
+    pub const DCD: Self = Self(1 << 2);
+    /// nUARTCTS modem interrupt, bit 1.
+    pub const CTS: Self = Self(1 << 1);
+    /// nUARTRI modem interrupt, bit 0.
+    pub const RI: Self = Self(1 << 0);
+
+    /// The four receive error interrupts: overrun, break, parity and framing.
+    pub const ERRORS: Self = Self(0b1111 << 7);
+    /// The four modem status interrupts.
+    pub const MODEM: Self = Self(0b1111);
+
+    /// No interrupt sources.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// All eleven interrupt sources.
+    pub const fn all() -> Self {
+        Self(0x07FF)
+    }
+
+    /// The raw bits, as they are laid out in the interrupt registers.
+    pub const fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// Builds a set from the raw bits of an interrupt register, dropping the reserved bits above bit 10.
+    pub const fn from_bits_truncate(bits: u16) -> Self {
+        Self(bits & Self::all().0)
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns whether every source in `other` is also in `self`.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns whether any source in `other` is also in `self`.
+    pub const fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// Iterates over the sources in this set, in the order of [`InterruptSource::ALL`].
+    pub fn iter(self) -> impl Iterator<Item = InterruptSource> {
+        InterruptSource::ALL
+            .into_iter()
+            .filter(move |source| self.contains(source.interrupts()))
+    }
+}
+
+impl From<InterruptSource> for Interrupts {
+    fn from(source: InterruptSource) -> Self {
+        source.interrupts()
+    }
+}
+
+impl ops::BitOr for Interrupts {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl ops::BitOrAssign for Interrupts {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl ops::BitAnd for Interrupts {
+    type Output = Self;
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl ops::BitAndAssign for Interrupts {
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.0 &= rhs.0;
+    }
+}
+
+/// The sources in `self` that aren't in `rhs`.
+impl ops::Sub for Interrupts {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 & !rhs.0)
+    }
+}
+
+/// Every source that isn't in `self`.
+impl ops::Not for Interrupts {
+    type Output = Self;
+    fn not(self) -> Self {
+        Self::all() - self
+    }
+}
+
+/// Lists the set flags, e.g. `Interrupts(RX_TIMEOUT | RX)`.
+impl fmt::Debug for Interrupts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const NAMES: [&str; 11] = [
+            "OVERRUN",
+            "BREAK",
+            "PARITY",
+            "FRAMING",
+            "RX_TIMEOUT",
+            "TX",
+            "RX",
+            "DSR",
+            "DCD",
+            "CTS",
+            "RI",
+        ];
+        f.write_str("Interrupts(")?;
+        let mut first = true;
+        for (source, name) in InterruptSource::ALL.into_iter().zip(NAMES) {
+            if self.contains(source.interrupts()) {
+                if !first {
+                    f.write_str(" | ")?;
+                }
+                f.write_str(name)?;
+                first = false;
+            }
+        }
+        f.write_str(")")
+    }
+}
+
+/// Adds `interrupts`/`from_interrupts` and the `From` conversions to the registers with the shared interrupt layout.
+macro_rules! interrupt_registers {
+    ($($register:ident),*) => {
+        $(
+            impl $register {
+                /// The interrupt sources whose bits are set.
+                pub const fn interrupts(&self) -> Interrupts {
+                    Interrupts::from_bits_truncate(self.bits())
+                }
+
+                /// Builds the register with the bits of `interrupts` set and every other bit cleared.
+                pub const fn from_interrupts(interrupts: Interrupts) -> Self {
+                    Self::from_bits(interrupts.bits())
+                }
+            }
+
+            impl From<Interrupts> for $register {
+                fn from(interrupts: Interrupts) -> Self {
+                    Self::from_interrupts(interrupts)
+                }
+            }
+
+            impl From<$register> for Interrupts {
+                fn from(register: $register) -> Self {
+                    register.interrupts()
+                }
+            }
+        )*
+    };
+}
+
+interrupt_registers!(
+    InterruptMaskSetClearRegister,
+    RawInterruptStatusRegister,
+    MaskedInterruptStatusRegister,
+    InterruptClearRegister
+);
+
+impl<T: BaseAddress, A> UART<T, A> {
+    /// Reads the masked interrupt status register once and iterates over the sources that are pending.
+    ///
+    /// With the `defmt` feature enabled, [`InterruptSource`] implements `defmt::Format`,
+    /// so an interrupt handler can cheaply log what fired:
+    ///
+    /// ```ignore
+    /// for source in uart.pending_interrupt_sources() {
+    ///     defmt::trace!("uart irq: {}", source);
+    /// }
+    /// ```
+    pub fn pending_interrupt_sources(&self) -> impl Iterator<Item = InterruptSource> + use<T, A> {
+        let status = self.read_masked_interrupt_status_register();
+        InterruptSource::ALL
+            .into_iter()
+            .filter(move |source| source.is_pending(&status))
+    }
+}
+
+impl<T: BaseAddress, A> UART<T, A> {
+    /// Reads the masked interrupt status register, i.e. the enabled interrupt sources that are asserted.
+    pub fn pending_interrupts(&self) -> Interrupts {
+        self.read_masked_interrupt_status_register().interrupts()
+    }
+}
+
+impl<T: BaseAddress> UART<T> {
+    /// Clears the interrupt sources in `which` with a single write of the interrupt clear register.
+    ///
+    /// Sources not in `which` are left alone. In an interrupt handler, read what fired with
+    /// [`UART::pending_interrupts`], handle it, and then clear exactly those sources.
+    /// The receive error interrupts (overrun, break, parity and framing) are best cleared after the data register
+    /// has been read, as the error is reported alongside the character it happened on.
+    pub fn clear_interrupts(&self, which: Interrupts) {
+        self.write_interrupt_clear_register(which.into());
+    }
+}
+
+/// Callbacks for [`UART::service_interrupts`], one per kind of interrupt. Every method does nothing by default.
+///
+/// The callbacks get no access to the UART, so a handler that needs it keeps its own copy of the `UART` handle,
+/// which is cheap as `UART` is `Copy`.
+pub trait UartHandlers {
+    /// The receive FIFO reached its trigger level.
+    ///
+    /// This should drain the receive FIFO, e.g. with [`UART::drain_rx`]. The interrupt is cleared afterwards,
+    /// but it's asserted again straight away while the FIFO is still at or above the trigger level.
+    fn on_rx(&mut self) {}
+
+    /// Characters have been waiting in the receive FIFO for 32 bit periods without reaching the trigger level.
+    ///
+    /// Like [`UartHandlers::on_rx`] this should drain the receive FIFO, which is what really clears it.
+    /// See [`UART::rx_timeout_ns`] for how long the wait is at the current baud rate.
+    fn on_rx_timeout(&mut self) {}
+
+    /// The transmit FIFO dropped to its trigger level, and there is room to write more data.
+    fn on_tx(&mut self) {}
+
+    /// A character was lost because it arrived while the receive FIFO was full.
+    fn on_overrun(&mut self) {}
+
+    /// A break condition was received.
+    fn on_break(&mut self) {}
+
+    /// A character was received with a parity error.
+    fn on_parity_error(&mut self) {}
+
+    /// A character was received without a valid stop bit.
+    fn on_framing_error(&mut self) {}
+
+    /// One or more of the modem status inputs changed, `changed` holds the ones that did.
+    ///
+    /// Read the current levels with [`UART::modem_status`].
+    fn on_modem(&mut self, changed: Interrupts) {
+        let _ = changed;
+    }
+}
+
+impl<T: BaseAddress> UART<T> {
+    /// Reads the masked interrupt status register once, calls the matching callbacks of `handlers`
+    /// and clears the interrupts it found. Returns the interrupts that were handled.
+    ///
+    /// The receive callbacks run first, then the receive errors, then transmit and finally the modem inputs.
+    /// The error interrupts are reported with the character they happened on, so by the time they're cleared
+    /// `on_rx`/`on_rx_timeout` have had the chance to read the data that caused them. The interrupt clear register
+    /// is written once at the end, with exactly the interrupts that were pending when the status was read;
+    /// anything that became pending in the meantime stays asserted for the next call.
+    ///
+    /// The handlers usually own a copy of the `UART` handle. Copy it out before the call, as `handlers` is borrowed
+    /// mutably for the duration of it:
+    ///
+    /// ```no_run
+    /// use pl011_uart_registers::{FixedAddress, UART, interrupts::UartHandlers};
+    ///
+    /// struct Console {
+    ///     uart: UART<FixedAddress<0x0900_0000>>,
+    ///     buffer: Vec<u8>,
+    /// }
+    ///
+    /// impl UartHandlers for Console {
+    ///     fn on_rx(&mut self) {
+    ///         self.buffer.extend(self.uart.drain_rx().filter_map(Result::ok));
+    ///     }
+    ///     fn on_rx_timeout(&mut self) {
+    ///         self.on_rx();
+    ///     }
+    /// }
+    ///
+    /// // called from the UART interrupt, e.g. with the console behind a critical section mutex
+    /// fn uart0_interrupt(console: &mut Console) {
+    ///     let uart = console.uart;
+    ///     uart.service_interrupts(console);
+    /// }
+    /// ```
+    pub fn service_interrupts(&self, handlers: &mut impl UartHandlers) -> Interrupts {
+        let pending = self.pending_interrupts();
+        if pending.contains(Interrupts::RX) {
+            handlers.on_rx();
+        }
+        if pending.contains(Interrupts::RX_TIMEOUT) {
+            handlers.on_rx_timeout();
+        }
+        if pending.contains(Interrupts::OVERRUN) {
+            handlers.on_overrun();
+        }
+        if pending.contains(Interrupts::BREAK) {
+            handlers.on_break();
+        }
+        if pending.contains(Interrupts::PARITY) {
+            handlers.on_parity_error();
+        }
+        if pending.contains(Interrupts::FRAMING) {
+            handlers.on_framing_error();
+        }
+        if pending.contains(Interrupts::TX) {
+            handlers.on_tx();
+        }
+        if pending.intersects(Interrupts::MODEM) {
+            handlers.on_modem(pending & Interrupts::MODEM);
+        }
+        if !pending.is_empty() {
+            self.clear_interrupts(pending);
+        }
+        pending
+    }
+}
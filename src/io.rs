@@ -0,0 +1,77 @@
+//! `embedded-io` traits, enabled by the `embedded-io` feature.
+use crate::{BaseAddress, UART, blocking::ReceiveError, registrers::DataRegister};
+use embedded_io::{ErrorKind, ErrorType, Read, ReadReady, Write, WriteReady};
+
+impl embedded_io::Error for ReceiveError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            ReceiveError::Parity | ReceiveError::Framing => ErrorKind::InvalidData,
+            ReceiveError::Overrun | ReceiveError::Break => ErrorKind::Other,
+        }
+    }
+}
+
+impl<T: BaseAddress> ErrorType for UART<T> {
+    type Error = ReceiveError;
+}
+
+impl<T: BaseAddress> Read for UART<T> {
+    /// Blocks until at least one byte has been received, then reads as many as are waiting in the receive FIFO,
+    /// up to the length of `buf`.
+    ///
+    /// A character received with an error ends the read with that error. The bytes read before it in the same call
+    /// are stored in `buf` but not reported.
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        buf[0] = self.read_byte()?;
+        let mut read = 1;
+        while read < buf.len() {
+            match self.try_read_byte() {
+                Some(byte) => buf[read] = byte?,
+                None => break,
+            }
+            read += 1;
+        }
+        Ok(read)
+    }
+}
+
+impl<T: BaseAddress> ReadReady for UART<T> {
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.read_flag_register().can_receive())
+    }
+}
+
+impl<T: BaseAddress> Write for UART<T> {
+    /// Blocks until there is room for at least one byte in the transmit FIFO, then writes as many bytes
+    /// as fit without blocking again.
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let Some((&first, rest)) = buf.split_first() else {
+            return Ok(0);
+        };
+        self.write_byte(first);
+        let mut written = 1;
+        for &byte in rest {
+            if self.read_flag_register().transmit_fifo_full() {
+                break;
+            }
+            self.write_data_register(DataRegister::default().with_data(byte));
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    /// Blocks until the transmit FIFO is empty and the UART is no longer busy, see [`UART::flush`].
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        UART::flush(self);
+        Ok(())
+    }
+}
+
+impl<T: BaseAddress> WriteReady for UART<T> {
+    fn write_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.read_flag_register().can_transmit())
+    }
+}
@@ -0,0 +1,88 @@
+//! Picking the IrDA low-power divisor for a UARTCLK frequency.
+//!
+//! In low-power IrDA mode the width of the transmitted pulses is derived from IrLPBaud16, which is UARTCLK divided by
+//! the divisor in the [`IrDALowPowerRegister`]. IrLPBaud16 is nominally 1.8432 MHz and must stay strictly between
+//! 1.42 MHz and 2.12 MHz, which gives a pulse width of 1.41 µs to 2.11 µs (three periods of IrLPBaud16).
+//!
+//! The divisor matters on the receive side too: the TRM notes that in low-power mode the UART rejects random noise
+//! on the received serial data input by ignoring SIRIN pulses that are shorter than 3 periods of IrLPBaud16.
+//! A divisor outside of the window makes the UART either reject valid pulses or accept noise as data.
+//!
+//! ```
+//! use pl011_uart_registers::{irda::IrdaError, registrers::IrDALowPowerRegister};
+//!
+//! let divisor = |clock| IrDALowPowerRegister::for_clock(clock).map(|ilpr| ilpr.bits());
+//! assert_eq!(divisor(3_686_400), Ok(2));
+//! assert_eq!(divisor(7_372_800), Ok(4));
+//! assert_eq!(divisor(14_745_600), Ok(8));
+//! assert_eq!(divisor(24_000_000), Ok(13));
+//! assert_eq!(divisor(48_000_000), Ok(26));
+//! assert_eq!(divisor(100_000_000), Ok(54));
+//! assert_eq!(divisor(1_000_000), Err(IrdaError::ClockTooSlow));
+//! assert_eq!(divisor(2_500_000), Err(IrdaError::NoDivisor));
+//! assert_eq!(divisor(600_000_000), Err(IrdaError::ClockTooFast));
+//! ```
+use crate::registrers::IrDALowPowerRegister;
+
+/// The nominal frequency of IrLPBaud16 in Hz.
+pub const IRLPBAUD16_HZ: u32 = 1_843_200;
+
+/// IrLPBaud16 must be above this frequency in Hz.
+const MIN_HZ: u64 = 1_420_000;
+
+/// IrLPBaud16 must be below this frequency in Hz.
+const MAX_HZ: u64 = 2_120_000;
+
+/// Why no IrDA low-power divisor exists for a UARTCLK frequency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum IrdaError {
+    /// UARTCLK is too slow, IrLPBaud16 is below 1.42 MHz even with a divisor of 1.
+    ClockTooSlow,
+    /// UARTCLK is too fast, IrLPBaud16 is above 2.12 MHz even with the largest divisor of 255.
+    ClockTooFast,
+    /// No divisor puts IrLPBaud16 in the window, which happens for UARTCLK between 2.12 MHz and 2.84 MHz,
+    /// and between 4.24 MHz and 4.26 MHz.
+    NoDivisor,
+}
+
+impl IrDALowPowerRegister {
+    /// Picks the divisor that brings IrLPBaud16 closest to its nominal 1.8432 MHz for a UARTCLK of `uart_clk_hz`,
+    /// out of the divisors that put it strictly between 1.42 MHz and 2.12 MHz.
+    ///
+    /// Fails if there is no such divisor, see the [module documentation](crate::irda).
+    pub const fn for_clock(uart_clk_hz: u32) -> Result<Self, IrdaError> {
+        let clock = uart_clk_hz as u64;
+        let target = IRLPBAUD16_HZ as u64;
+        // the divisors on either side of the target, tried closest to it first
+        let below = clock / target;
+        let above = below + 1;
+        let (nearest, other) = if below >= u8::MAX as u64 {
+            (u8::MAX as u64, u8::MAX as u64)
+        } else if below == 0 || (clock - target * below) * above > (target * above - clock) * below
+        {
+            (above, below)
+        } else {
+            (below, above)
+        };
+        if in_window(clock, nearest) {
+            Ok(Self::from_bits(nearest as u8))
+        } else if in_window(clock, other) {
+            Ok(Self::from_bits(other as u8))
+        } else if clock <= MIN_HZ {
+            Err(IrdaError::ClockTooSlow)
+        } else if clock >= MAX_HZ * u8::MAX as u64 {
+            Err(IrdaError::ClockTooFast)
+        } else {
+            Err(IrdaError::NoDivisor)
+        }
+    }
+}
+
+/// Whether `divisor` is a valid register value that puts IrLPBaud16 strictly inside the window for `clock`.
+const fn in_window(clock: u64, divisor: u64) -> bool {
+    divisor >= 1
+        && divisor <= u8::MAX as u64
+        && MIN_HZ * divisor < clock
+        && clock < MAX_HZ * divisor
+}
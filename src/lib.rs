@@ -2,12 +2,94 @@
 //! this crate implements the registers of the [ARM PrimeCell UART (PL011)](https://developer.arm.com/documentation/ddi0183/g) peripheral
 //! it doesn't implement the full peripheral, just the registers. Making it easier to write your own drivers :)
 
+#[cfg(feature = "std")]
+extern crate std;
+
+#[macro_use]
+mod macros;
+
+pub mod access;
+pub mod baud;
+pub mod block;
+pub mod blocking;
+pub mod config;
+pub mod fifo;
+#[cfg(feature = "defmt")]
+mod format;
+pub mod frame;
+pub mod interrupts;
+#[cfg(feature = "embedded-io")]
+mod io;
+pub mod irda;
+#[cfg(feature = "heapless")]
+pub mod line;
+pub mod mode;
+pub mod modem;
+#[cfg(feature = "embedded-hal")]
+mod nb_serial;
+pub mod offsets;
 pub mod registrers;
+pub mod scoped;
+#[cfg(feature = "serde")]
+mod serialize;
+pub mod snapshot;
+pub mod split;
+pub mod stats;
+#[cfg(feature = "test-util")]
+pub mod testing;
+pub mod xonxoff;
+
+pub use config::init_console;
+
+use access::{ReadOnly, ReadWrite};
+use core::marker::PhantomData;
+
+/// How register reads and writes reach the hardware.
+///
+/// The default methods are plain volatile loads and stores, which is what every normal target wants.
+/// Platforms where MMIO has to go through something else (e.g. a hypervisor call or a bus-specific accessor)
+/// can override them on their own [`BaseAddress`] type and reuse all the register definitions and helpers.
+///
+/// The `*_ptr` accessors on [`UART`] return raw pointers and so bypass this trait.
+///
+/// With the `test-util` feature, `testing::RegisterFile` implements this on top of plain memory,
+/// so driver code can be run and checked on the host.
+pub trait MmioAccess {
+    /// Reads an `R` from the address `addr`.
+    ///
+    /// # Safety
+    /// `addr` must be a readable register that is suitably aligned for `R`,
+    /// and every value the hardware can return there must be a valid `R`.
+    unsafe fn read<R>(&self, addr: usize) -> R {
+        unsafe { (addr as *const R).read_volatile() }
+    }
+
+    /// Writes `value` to the address `addr`.
+    ///
+    /// # Safety
+    /// `addr` must be a writable register that is suitably aligned for `R`.
+    unsafe fn write<R>(&self, addr: usize, value: R) {
+        unsafe { (addr as *mut R).write_volatile(value) }
+    }
+}
+
+/// The value of the combined PrimeCell identification registers on every PrimeCell peripheral, see [`UART::is_primecell`].
+pub const PRIMECELL_ID: u32 = 0xB105_F00D;
 
 /// This trait is used to get the base address of the peripheral.
 /// by using a trait it can be a constant or a runtime value.
-pub trait BaseAddress: Copy {
+///
+/// Register accesses go through its [`MmioAccess`] supertrait; an empty `impl MmioAccess for MyAddress {}` gives the
+/// default volatile accesses.
+pub trait BaseAddress: Copy + MmioAccess {
     fn base_address(self) -> usize;
+
+    /// The address of the register at `offset` in the PL011 register map, which by default is `offset` bytes
+    /// past the base address. Override this for integrations that space the registers differently,
+    /// see [`StridedAddress`].
+    fn register_address(self, offset: usize) -> usize {
+        self.base_address().wrapping_add(offset)
+    }
 }
 
 // for a runtime value, we can use a usize
@@ -16,6 +98,7 @@ impl BaseAddress for usize {
         self
     }
 }
+impl MmioAccess for usize {}
 
 /// for a fixed address, we can use a struct with a const generic parameter
 /// this is a zero-sized type, allowing is to use zero-sized and type-safe register blocks
@@ -26,268 +109,571 @@ impl<const BASE: usize> BaseAddress for FixedAddress<BASE> {
         BASE
     }
 }
+impl<const BASE: usize> MmioAccess for FixedAddress<BASE> {}
 
-/// This is the main struct for the UART peripheral.
-///
-/// It takes a base address as a generic parameter.
-/// This allows us to use a fixed address or a runtime value.
-/// When `T = FixedAddress<BASE>`, the base address is a constant and this is a zero-sized type.
-/// When `T = usize`, the base address is a runtime value and this struct is the size of a pointer.
-#[derive(Debug, Clone, Copy)]
-pub struct UART<T: BaseAddress> {
-    base: T,
+/// for an address that is only known at runtime and has to be recomputed on every access,
+/// e.g. because the MMIO window can be remapped. Function items have to be cast to the pointer type:
+/// `UART::new(current_base as fn() -> usize)`.
+impl BaseAddress for fn() -> usize {
+    fn base_address(self) -> usize {
+        self()
+    }
 }
+impl MmioAccess for fn() -> usize {}
 
-//ideally we generate this with a macro
-// whose input looks a little like this
-// ```
-// #[register_block]
-// struct UART {
-//     #[register(0x00, RW)]
-//     data_register: DataRegister,
-//     #[register(0x04, RO)]
-//     receive_status_register: ReceiveStatusRegister,
-//     #[register(0x04, ClearAll)]
-//     error_clear_register: u32, //needs a size, but doesn't take an arg because it's a clear-all value
-//     #[register(0x18, RO)]
-//     flag_register: FlagRegister,
-//     #[register(0x20, RW)]
-//     irda_low_power_register: IrDALowPowerRegister,
-//     #[register(0x24, RW)]
-//     integer_baud_rate_divisor_register: IntegerBaudRateDivisorRegister,
-//     #[register(0x28, RW)]
-//     fractional_baud_rate_divisor_register: FractionalBaudRateDivisorRegister,
-//     #[register(0x2C, RW)]
-//     line_control_register: LineControlRegister,
-//     #[register(0x30, RW)]
-//     control_register: ControlRegister,
-//     #[register(0x34, RW)]
-//     interrupt_fifo_level_select_register: InterruptFIFOLevelSelectRegister,
-//     #[register(0x38, RW)]
-//     interrupt_mask_set_clear_register: InterruptMaskSetClearRegister,
-//     #[register(0x3C, RO)]
-//     raw_interrupt_status_register: RawInterruptStatusRegister,
-//     #[register(0x40, RO)]
-//     masked_interrupt_status_register: MaskedInterruptStatusRegister,
-//     #[register(0x44, WO)]
-//     interrupt_clear_register: InterruptClearRegister,
-//     #[register(0x48, RW)]
-//     dma_control_register: DMAControlRegister,
-// }
-// ```
-
-impl<T: BaseAddress> UART<T> {
-    pub const fn new(base: T) -> Self {
-        UART { base }
+/// like `fn() -> usize`, but for closures that capture state, e.g. a lazily initialized mapping.
+impl BaseAddress for &dyn Fn() -> usize {
+    fn base_address(self) -> usize {
+        self()
     }
+}
+impl MmioAccess for &dyn Fn() -> usize {}
 
-    unsafe fn read_register<R>(self, offset: usize) -> R {
-        unsafe {
-            let raw = (self.base.base_address() as *const u8).add(offset) as *const R;
-            raw.read_volatile()
-        }
+/// Adds `EXTRA` to the base address produced by `A`.
+///
+/// For integrations where the registers sit at a fixed offset inside a larger address window,
+/// e.g. `UART<OffsetAddress<usize, 0x1000>>` for a PL011 0x1000 bytes into a window whose start is known at runtime.
+/// The offset is a constant, so with a [`FixedAddress`] inside this is still a zero-sized type.
+#[derive(Debug, Clone, Copy)]
+pub struct OffsetAddress<A, const EXTRA: usize>(pub A);
+impl<A: BaseAddress, const EXTRA: usize> BaseAddress for OffsetAddress<A, EXTRA> {
+    fn base_address(self) -> usize {
+        self.0.base_address().wrapping_add(EXTRA)
     }
-
-    unsafe fn write_register<R>(self, offset: usize, value: R) {
-        unsafe {
-            let raw = (self.base.base_address() as *mut u8).add(offset) as *mut R;
-            raw.write_volatile(value);
-        }
+}
+impl<A: MmioAccess, const EXTRA: usize> MmioAccess for OffsetAddress<A, EXTRA> {
+    unsafe fn read<R>(&self, addr: usize) -> R {
+        unsafe { self.0.read(addr) }
     }
 
-    unsafe fn update_register<R, F>(self, offset: usize, f: F)
-    where
-        F: FnOnce(R) -> R,
-    {
-        unsafe { self.write_register::<R>(offset, f(self.read_register::<R>(offset))) };
+    unsafe fn write<R>(&self, addr: usize, value: R) {
+        unsafe { self.0.write(addr, value) }
     }
+}
 
-    /// data register, read/write, offset 0x00
-    pub fn read_data_register(&self) -> registrers::DataRegister {
-        unsafe { self.read_register(0x00) }
+/// Spaces the registers `STRIDE` times further apart than in the PL011 register map.
+///
+/// For integrations that place every 32-bit register slot on a wider bus stride: with `STRIDE = 4` the flag register,
+/// normally at offset 0x18, is accessed at offset 0x60 from the base address of `A`.
+/// Only the position of each register changes, every access keeps the width of its register.
+/// [`UART::as_block_ptr`] doesn't take the stride into account, as the [`block::RegisterBlock`] layout is fixed.
+#[derive(Debug, Clone, Copy)]
+pub struct StridedAddress<A, const STRIDE: usize>(pub A);
+impl<A: BaseAddress, const STRIDE: usize> BaseAddress for StridedAddress<A, STRIDE> {
+    fn base_address(self) -> usize {
+        self.0.base_address()
     }
 
-    pub fn write_data_register(&self, value: registrers::DataRegister) {
-        unsafe { self.write_register(0x00, value) }
+    fn register_address(self, offset: usize) -> usize {
+        self.0.register_address(offset.wrapping_mul(STRIDE))
     }
-
-    pub fn update_data_register<F>(&self, f: F)
-    where
-        F: FnOnce(registrers::DataRegister) -> registrers::DataRegister,
-    {
-        unsafe { self.update_register(0x00, f) };
+}
+impl<A: MmioAccess, const STRIDE: usize> MmioAccess for StridedAddress<A, STRIDE> {
+    unsafe fn read<R>(&self, addr: usize) -> R {
+        unsafe { self.0.read(addr) }
     }
 
-    /// Receive Status Register, read-only, offset 0x04
-    pub fn read_receive_status_register(&self) -> registrers::ReceiveStatusRegister {
-        unsafe { self.read_register(0x04) }
+    unsafe fn write<R>(&self, addr: usize, value: R) {
+        unsafe { self.0.write(addr, value) }
     }
+}
 
-    /// Error clear register, write-only, offset 0x04
-    // no value, just a clear-all but it does need to know the size of the register
-    pub fn write_error_clear_register(&self) {
-        unsafe { self.write_register(0x04, 0u32) }
-    }
+// handles with a computed base address are still `Copy`
+const _: () = {
+    const fn is_copy<T: Copy>() {}
+    is_copy::<UART<fn() -> usize>>();
+    is_copy::<UART<&dyn Fn() -> usize>>();
+};
 
-    /// Flag register, read-only, offset 0x18
-    pub fn read_flag_register(&self) -> registrers::FlagRegister {
-        unsafe { self.read_register(0x18) }
-    }
+/// This is the main struct for the UART peripheral.
+///
+/// It takes a base address as a generic parameter.
+/// This allows us to use a fixed address or a runtime value.
+/// When `T = FixedAddress<BASE>`, the base address is a constant and this is a zero-sized type.
+/// When `T = usize`, the base address is a runtime value and this struct is the size of a pointer.
+///
+/// The second parameter is a zero-sized marker for the kind of access the handle allows.
+/// It defaults to [`ReadWrite`], use [`UART::read_only`] to get a `UART<T, ReadOnly>` that only has the `read_*` methods
+/// without side effects. Reads that pop a FIFO, like [`UART::read_data_register`], need a read/write handle.
+#[derive(Debug, Clone, Copy)]
+pub struct UART<T: BaseAddress, A = ReadWrite> {
+    base: T,
+    _access: PhantomData<A>,
+}
 
-    /// irda low power register, read/write, offset 0x20
-    pub fn read_irda_low_power_register(&self) -> registrers::IrDALowPowerRegister {
-        unsafe { self.read_register(0x20) }
-    }
-    pub fn write_irda_low_power_register(&self, value: registrers::IrDALowPowerRegister) {
-        unsafe { self.write_register(0x20, value) }
-    }
-    pub fn update_irda_low_power_register<F>(&self, f: F)
-    where
-        F: FnOnce(registrers::IrDALowPowerRegister) -> registrers::IrDALowPowerRegister,
-    {
-        unsafe { self.update_register(0x20, f) };
-    }
+// The typed accessors of every register. Adding a register here is all it takes to get its
+// `read_*`/`write_*`/`update_*` methods, see `macros.rs` for the syntax.
+register_block! {
+    offsets::DATA => DataRegister {
+        /// data register, read/write, offset 0x00
+        ///
+        /// Reading this register is destructive: it pops the oldest character off the receive FIFO, and the
+        /// character is gone unless the returned value is used. Only read it to receive data, never to inspect
+        /// the state of the UART. The error flags of the last character read are also in the receive status register,
+        /// see [`UART::read_receive_status_register`], and [`UART::snapshot`] dumps the registers without touching
+        /// this one.
+        #[must_use = "reading the data register pops the receive FIFO, the character is lost if it isn't used"]
+        pop: read_data_register,
+        /// Pointer to the Data Register, offset 0x00.
+        ptr: data_register_ptr,
+    },
+    offsets::RECEIVE_STATUS => ReceiveStatusRegister {
+        /// Receive Status Register, read-only, offset 0x04
+        ///
+        /// Reading this has no side effects, unlike reading the data register. It holds the error flags of the last
+        /// character read from the data register, plus the overrun flag as soon as an overrun happens.
+        read: read_receive_status_register,
+        /// Pointer to the Receive Status Register, offset 0x04.
+        ptr: receive_status_register_ptr,
+    },
+    offsets::ERROR_CLEAR => ErrorClearRegister {
+        /// Error clear register, write-only, offset 0x04
+        ///
+        /// Clears the framing, parity, break, and overrun errors. The value written is ignored by the hardware, so this takes no argument.
+        clear: write_error_clear_register,
+        /// Pointer to the Error Clear Register, offset 0x04.
+        ptr: error_clear_register_ptr,
+    },
+    offsets::FLAG => FlagRegister {
+        /// Flag register, read-only, offset 0x18
+        read: read_flag_register,
+        /// Pointer to the Flag Register, offset 0x18.
+        ptr: flag_register_ptr,
+    },
+    offsets::IRDA_LOW_POWER => IrDALowPowerRegister {
+        /// irda low power register, read/write, offset 0x20
+        read: read_irda_low_power_register,
+        /// irda low power register, read/write, offset 0x20
+        write: write_irda_low_power_register,
+        update: update_irda_low_power_register,
+        /// Pointer to the IrDA Low-Power Counter Register, offset 0x20.
+        ptr: irda_low_power_register_ptr,
+    },
+    offsets::INTEGER_BAUD_RATE_DIVISOR => IntegerBaudRateDivisorRegister {
+        /// Integer Baud Rate Divisor Register, read/write, offset 0x24
+        read: read_integer_baud_rate_divisor_register,
+        /// Integer Baud Rate Divisor Register, read/write, offset 0x24
+        write: write_integer_baud_rate_divisor_register,
+        update: update_integer_baud_rate_divisor_register,
+        /// Pointer to the Integer Baud Rate Divisor Register, offset 0x24.
+        ptr: integer_baud_rate_divisor_register_ptr,
+    },
+    offsets::FRACTIONAL_BAUD_RATE_DIVISOR => FractionalBaudRateDivisorRegister {
+        /// Fractional Baud Rate Divisor Register, read/write, offset 0x28
+        read: read_fractional_baud_rate_divisor_register,
+        /// Fractional Baud Rate Divisor Register, read/write, offset 0x28
+        write: write_fractional_baud_rate_divisor_register,
+        update: update_fractional_baud_rate_divisor_register,
+        /// Pointer to the Fractional Baud Rate Divisor Register, offset 0x28.
+        ptr: fractional_baud_rate_divisor_register_ptr,
+    },
+    offsets::LINE_CONTROL => LineControlRegister {
+        /// Line Control Register, read/write, offset 0x2C
+        read: read_line_control_register,
+        /// Line Control Register, read/write, offset 0x2C
+        write: write_line_control_register,
+        update: update_line_control_register,
+        /// Pointer to the Line Control Register, offset 0x2C.
+        ptr: line_control_register_ptr,
+    },
+    offsets::CONTROL => ControlRegister {
+        /// Control Register, read/write, offset 0x30
+        read: read_control_register,
+        /// Control Register, read/write, offset 0x30
+        write: write_control_register,
+        update: update_control_register,
+        /// Pointer to the Control Register, offset 0x30.
+        ptr: control_register_ptr,
+    },
+    offsets::INTERRUPT_FIFO_LEVEL_SELECT => InterruptFIFOLevelSelectRegister {
+        /// Interrupt FIFO Level Select Register, read/write, offset 0x34
+        read: read_interrupt_fifo_level_select_register,
+        /// Interrupt FIFO Level Select Register, read/write, offset 0x34
+        write: write_interrupt_fifo_level_select_register,
+        update: update_interrupt_fifo_level_select_register,
+        /// Pointer to the Interrupt FIFO Level Select Register, offset 0x34.
+        ptr: interrupt_fifo_level_select_register_ptr,
+    },
+    offsets::INTERRUPT_MASK_SET_CLEAR => InterruptMaskSetClearRegister {
+        /// Interrupt Mask Set/Clear Register, read/write, offset 0x38
+        read: read_interrupt_mask_set_clear_register,
+        /// Interrupt Mask Set/Clear Register, read/write, offset 0x38
+        write: write_interrupt_mask_set_clear_register,
+        update: update_interrupt_mask_set_clear_register,
+        /// Pointer to the Interrupt Mask Set/Clear Register, offset 0x38.
+        ptr: interrupt_mask_set_clear_register_ptr,
+    },
+    offsets::RAW_INTERRUPT_STATUS => RawInterruptStatusRegister {
+        /// Raw Interrupt Status Register, read-only, offset 0x3C
+        read: read_raw_interrupt_status_register,
+        /// Pointer to the Raw Interrupt Status Register, offset 0x3C.
+        ptr: raw_interrupt_status_register_ptr,
+    },
+    offsets::MASKED_INTERRUPT_STATUS => MaskedInterruptStatusRegister {
+        /// Masked Interrupt Status Register, read-only, offset 0x40
+        read: read_masked_interrupt_status_register,
+        /// Pointer to the Masked Interrupt Status Register, offset 0x40.
+        ptr: masked_interrupt_status_register_ptr,
+    },
+    offsets::INTERRUPT_CLEAR => InterruptClearRegister {
+        /// Interrupt Clear Register, write-only, offset 0x44
+        ///
+        /// A single write without a read first: every interrupt set to 1 is cleared and the others are left alone,
+        /// so there is nothing to read-modify-write.
+        write: write_interrupt_clear_register,
+        /// Pointer to the Interrupt Clear Register, offset 0x44.
+        ptr: interrupt_clear_register_ptr,
+    },
+    offsets::DMA_CONTROL => DMAControlRegister {
+        /// DMA Control Register, read/write, offset 0x48
+        read: read_dma_control_register,
+        /// DMA Control Register, read/write, offset 0x48
+        write: write_dma_control_register,
+        update: update_dma_control_register,
+        /// Pointer to the DMA Control Register, offset 0x48.
+        ptr: dma_control_register_ptr,
+    },
+    offsets::TEST_CONTROL => TestControlRegister {
+        /// Test Control Register, read/write, offset 0x80
+        read: read_test_control_register,
+        /// Test Control Register, read/write, offset 0x80
+        ///
+        /// Only for integration and production test, see [`registrers::TestControlRegister`].
+        /// Setting any of its bits changes how the UART behaves, so only do so while no other code is using the UART.
+        write: write_test_control_register,
+        update: update_test_control_register,
+        /// Pointer to the Test Control Register, offset 0x80.
+        ptr: test_control_register_ptr,
+    },
+    offsets::INTEGRATION_TEST_INPUT => IntegrationTestInputRegister {
+        /// Integration Test Input Register, read-only, offset 0x84
+        ///
+        /// Only meaningful in integration test mode, see [`registrers::TestControlRegister`].
+        read: read_integration_test_input_register,
+        /// Pointer to the Integration Test Input Register, offset 0x84.
+        ptr: integration_test_input_register_ptr,
+    },
+    offsets::INTEGRATION_TEST_OUTPUT => IntegrationTestOutputRegister {
+        /// Integration Test Output Register, read/write, offset 0x88
+        ///
+        /// Only meaningful in integration test mode, see [`registrers::TestControlRegister`].
+        read: read_integration_test_output_register,
+        /// Integration Test Output Register, read/write, offset 0x88
+        ///
+        /// Only for integration test mode, see [`registrers::TestControlRegister`]. In that mode this drives the UART's outputs directly.
+        write: write_integration_test_output_register,
+        update: update_integration_test_output_register,
+        /// Pointer to the Integration Test Output Register, offset 0x88.
+        ptr: integration_test_output_register_ptr,
+    },
+    offsets::INTEGRATION_TEST_DATA => IntegrationTestDataRegister {
+        /// Integration Test Data Register, read/write, offset 0x8C
+        ///
+        /// Only meaningful in FIFO test mode, see [`registrers::TestControlRegister`]. Reading pops the transmit FIFO.
+        pop: read_integration_test_data_register,
+        /// Integration Test Data Register, read/write, offset 0x8C
+        ///
+        /// Only for FIFO test mode, see [`registrers::TestControlRegister`]. Writing pushes the value into the receive FIFO.
+        write: write_integration_test_data_register,
+        /// Pointer to the Integration Test Data Register, offset 0x8C.
+        ptr: integration_test_data_register_ptr,
+    },
+    offsets::PERIPHERAL_ID_0 => PeripheralId0Register {
+        /// Peripheral Identification Register 0, read-only, offset 0xFE0
+        read: read_peripheral_id_0,
+        /// Pointer to the Peripheral Identification Register 0, offset 0xFE0.
+        ptr: peripheral_id_0_register_ptr,
+    },
+    offsets::PERIPHERAL_ID_1 => PeripheralId1Register {
+        /// Peripheral Identification Register 1, read-only, offset 0xFE4
+        read: read_peripheral_id_1,
+        /// Pointer to the Peripheral Identification Register 1, offset 0xFE4.
+        ptr: peripheral_id_1_register_ptr,
+    },
+    offsets::PERIPHERAL_ID_2 => PeripheralId2Register {
+        /// Peripheral Identification Register 2, read-only, offset 0xFE8
+        read: read_peripheral_id_2,
+        /// Pointer to the Peripheral Identification Register 2, offset 0xFE8.
+        ptr: peripheral_id_2_register_ptr,
+    },
+    offsets::PERIPHERAL_ID_3 => PeripheralId3Register {
+        /// Peripheral Identification Register 3, read-only, offset 0xFEC
+        read: read_peripheral_id_3,
+        /// Pointer to the Peripheral Identification Register 3, offset 0xFEC.
+        ptr: peripheral_id_3_register_ptr,
+    },
+    offsets::PRIMECELL_ID_0 => PrimeCellId0Register {
+        /// PrimeCell Identification Register 0, read-only, offset 0xFF0
+        read: read_primecell_id_0,
+        /// Pointer to the PrimeCell Identification Register 0, offset 0xFF0.
+        ptr: primecell_id_0_register_ptr,
+    },
+    offsets::PRIMECELL_ID_1 => PrimeCellId1Register {
+        /// PrimeCell Identification Register 1, read-only, offset 0xFF4
+        read: read_primecell_id_1,
+        /// Pointer to the PrimeCell Identification Register 1, offset 0xFF4.
+        ptr: primecell_id_1_register_ptr,
+    },
+    offsets::PRIMECELL_ID_2 => PrimeCellId2Register {
+        /// PrimeCell Identification Register 2, read-only, offset 0xFF8
+        read: read_primecell_id_2,
+        /// Pointer to the PrimeCell Identification Register 2, offset 0xFF8.
+        ptr: primecell_id_2_register_ptr,
+    },
+    offsets::PRIMECELL_ID_3 => PrimeCellId3Register {
+        /// PrimeCell Identification Register 3, read-only, offset 0xFFC
+        read: read_primecell_id_3,
+        /// Pointer to the PrimeCell Identification Register 3, offset 0xFFC.
+        ptr: primecell_id_3_register_ptr,
+    },
+}
 
-    /// Integer Baud Rate Divisor Register, read/write, offset 0x24
-    pub fn read_integer_baud_rate_divisor_register(
-        &self,
-    ) -> registrers::IntegerBaudRateDivisorRegister {
-        unsafe { self.read_register(0x24) }
+impl<T: BaseAddress, A> UART<T, A> {
+    fn register_ptr<R>(&self, offset: usize) -> *mut R {
+        self.base.register_address(offset) as *mut R
     }
-    pub fn write_integer_baud_rate_divisor_register(
-        &self,
-        value: registrers::IntegerBaudRateDivisorRegister,
-    ) {
-        unsafe { self.write_register(0x24, value) }
+
+    /// Reads the register at `offset` from the base address as an `R`.
+    ///
+    /// This is what all the `read_*` methods are built on, and it's exposed so registers this crate doesn't model
+    /// (e.g. the extra registers of a PL011 derivative) can be read through the same handle with a user-defined register type.
+    /// The width of the access is the size of `R`, so `R` should be a register type backed by a `u8`, `u16` or `u32`.
+    /// The access itself goes through the base address' [`MmioAccess`] implementation.
+    ///
+    /// # Safety
+    /// `offset` must be a readable register of the peripheral that is suitably aligned for `R`,
+    /// and every value the hardware can return there must be a valid `R`.
+    /// On a [`ReadOnly`] handle the register must also be free of read side effects, so not one of the data registers.
+    pub unsafe fn read_register<R>(&self, offset: usize) -> R {
+        unsafe { self.base.read(self.base.register_address(offset)) }
     }
-    pub fn update_integer_baud_rate_divisor_register<F>(&self, f: F)
-    where
-        F: FnOnce(
-            registrers::IntegerBaudRateDivisorRegister,
-        ) -> registrers::IntegerBaudRateDivisorRegister,
-    {
-        unsafe { self.update_register(0x24, f) };
+
+    /// Reads the IrDA low-power counter register, returning the raw value if the divisor is zero and so invalid.
+    pub fn try_read_irda_low_power_register(&self) -> Result<registrers::IrDALowPowerRegister, u8> {
+        self.read_irda_low_power_register().validate()
     }
 
-    /// Fractional Baud Rate Divisor Register, read/write, offset 0x28
-    pub fn read_fractional_baud_rate_divisor_register(
+    /// Reads the integer baud rate divisor register, returning the raw value if the divisor is zero and so invalid.
+    pub fn try_read_integer_baud_rate_divisor_register(
         &self,
-    ) -> registrers::FractionalBaudRateDivisorRegister {
-        unsafe { self.read_register(0x28) }
+    ) -> Result<registrers::IntegerBaudRateDivisorRegister, u16> {
+        self.read_integer_baud_rate_divisor_register().validate()
     }
-    pub fn write_fractional_baud_rate_divisor_register(
+
+    /// Reads the interrupt FIFO level select register, returning the raw value if either level uses a reserved encoding.
+    pub fn try_read_interrupt_fifo_level_select_register(
         &self,
-        value: registrers::FractionalBaudRateDivisorRegister,
-    ) {
-        unsafe { self.write_register(0x28, value) }
-    }
-    pub fn update_fractional_baud_rate_divisor_register<F>(&self, f: F)
-    where
-        F: FnOnce(
-            registrers::FractionalBaudRateDivisorRegister,
-        ) -> registrers::FractionalBaudRateDivisorRegister,
-    {
-        unsafe { self.update_register(0x28, f) };
+    ) -> Result<registrers::InterruptFIFOLevelSelectRegister, u16> {
+        self.read_interrupt_fifo_level_select_register().validate()
     }
 
-    /// Line Control Register, read/write, offset 0x2C
-    pub fn read_line_control_register(&self) -> registrers::LineControlRegister {
-        unsafe { self.read_register(0x2C) }
-    }
-    pub fn write_line_control_register(&self, value: registrers::LineControlRegister) {
-        unsafe { self.write_register(0x2C, value) }
+    /// Reads the four peripheral identification registers and combines them into one value,
+    /// with UARTPeriphID0 in bits 0-7 up to UARTPeriphID3 in bits 24-31.
+    ///
+    /// For a PL011 this reads as `0x00r41011`, where `r` is the revision (e.g. `0x00341011` for r1p5).
+    /// Checking the low 20 bits is a cheap way to confirm that the base address really points at a PL011
+    /// before touching any of the other registers.
+    #[must_use]
+    pub fn read_peripheral_id(&self) -> u32 {
+        u32::from_le_bytes([
+            self.read_peripheral_id_0().bits(),
+            self.read_peripheral_id_1().bits(),
+            self.read_peripheral_id_2().bits(),
+            self.read_peripheral_id_3().bits(),
+        ])
     }
-    pub fn update_line_control_register<F>(&self, f: F)
-    where
-        F: FnOnce(registrers::LineControlRegister) -> registrers::LineControlRegister,
-    {
-        unsafe { self.update_register(0x2C, f) };
+
+    /// Reads the four PrimeCell identification registers and combines them into one value,
+    /// with UARTPCellID0 in bits 0-7 up to UARTPCellID3 in bits 24-31.
+    ///
+    /// This is always [`PRIMECELL_ID`], `0xB105F00D`, on a PrimeCell peripheral.
+    #[must_use]
+    pub fn read_primecell_id(&self) -> u32 {
+        u32::from_le_bytes([
+            self.read_primecell_id_0().bits(),
+            self.read_primecell_id_1().bits(),
+            self.read_primecell_id_2().bits(),
+            self.read_primecell_id_3().bits(),
+        ])
     }
 
-    /// Control Register, read/write, offset 0x30
-    pub fn read_control_register(&self) -> registrers::ControlRegister {
-        unsafe { self.read_register(0x30) }
+    /// Returns whether the PrimeCell identification registers read as [`PRIMECELL_ID`], `0xB105F00D`.
+    ///
+    /// A cheap presence check while probing: anything else means the base address doesn't point at a PrimeCell peripheral.
+    /// It doesn't tell a PL011 apart from other PrimeCells, use [`UART::read_peripheral_id`] for that.
+    pub fn is_primecell(&self) -> bool {
+        self.read_primecell_id() == PRIMECELL_ID
     }
-    pub fn write_control_register(&self, value: registrers::ControlRegister) {
-        unsafe { self.write_register(0x30, value) }
+}
+
+impl<T: BaseAddress> UART<T> {
+    pub const fn new(base: T) -> Self {
+        UART {
+            base,
+            _access: PhantomData,
+        }
     }
-    pub fn update_control_register<F>(&self, f: F)
-    where
-        F: FnOnce(registrers::ControlRegister) -> registrers::ControlRegister,
-    {
-        unsafe { self.update_register(0x30, f) };
+
+    /// Returns every register as a field with only the accesses it allows, e.g. `uart.registers().flag.read()`.
+    ///
+    /// See [`access::Registers`]. Unlike the flat `read_*`/`write_*` methods this makes it impossible to write
+    /// a read-only register or read a write-only one.
+    pub const fn registers(&self) -> access::Registers<T> {
+        access::Registers::new(self.base)
     }
 
-    /// Interrupt FIFO Level Select Register, read/write, offset 0x34
-    pub fn read_interrupt_fifo_level_select_register(
-        &self,
-    ) -> registrers::InterruptFIFOLevelSelectRegister {
-        unsafe { self.read_register(0x34) }
+    /// Returns a copy of this handle that can only read registers.
+    ///
+    /// This can be handed to code that should observe the UART (e.g. to poll status) without being able to reconfigure it.
+    /// It can't read the data registers either, as that would pop their FIFO and take the data away from its owner:
+    ///
+    /// ```compile_fail
+    /// # use pl011_uart_registers::UART;
+    /// let status = UART::new(0x0900_0000_usize).read_only();
+    /// let _ = status.read_data_register();
+    /// ```
+    pub const fn read_only(self) -> UART<T, ReadOnly> {
+        UART {
+            base: self.base,
+            _access: PhantomData,
+        }
     }
-    pub fn write_interrupt_fifo_level_select_register(
-        &self,
-        value: registrers::InterruptFIFOLevelSelectRegister,
-    ) {
-        unsafe { self.write_register(0x34, value) }
+
+    /// Reads the data register as a raw 32-bit word, with the data in bits 0-7 and the status flags in bits 8-11.
+    ///
+    /// This is a single volatile load without any decoding, for loops that drain the receive FIFO as fast as possible
+    /// and only decode the words they keep.
+    /// Like any read of the data register, this pops the receive FIFO; it doesn't check whether the FIFO is empty first.
+    #[must_use = "reading the data register pops the receive FIFO, the character is lost if it isn't used"]
+    pub fn read_data_raw(&self) -> u32 {
+        unsafe { self.read_register(offsets::DATA) }
     }
-    pub fn update_interrupt_fifo_level_select_register<F>(&self, f: F)
-    where
-        F: FnOnce(
-            registrers::InterruptFIFOLevelSelectRegister,
-        ) -> registrers::InterruptFIFOLevelSelectRegister,
-    {
-        unsafe { self.update_register(0x34, f) };
+
+    /// Reads the 32-bit word at `offset` from the base address, without decoding it.
+    ///
+    /// This is the escape hatch for registers this crate doesn't model, e.g. vendor-specific extensions some SoCs add
+    /// to the PL011, or for poking around while bringing up a board:
+    ///
+    /// ```ignore
+    /// // a vendor extension at 0xC0, past the standard registers
+    /// let value = uart.read_raw_u32(0xC0);
+    /// ```
+    ///
+    /// This is safe to call, but it's only as harmless as the register at `offset`. Reading the data register pops the
+    /// receive FIFO, and on some SoCs vendor registers have read side effects of their own. Nothing checks that a
+    /// register exists at `offset`; an offset that isn't backed by one may fault or hang the bus.
+    ///
+    /// `offset` must be 4-byte aligned and inside the 4 KiB register window, which is checked in debug builds.
+    /// Use [`UART::read_register`] for narrower accesses.
+    pub fn read_raw_u32(&self, offset: usize) -> u32 {
+        debug_assert!(
+            offset < offsets::REGISTER_WINDOW && offset.is_multiple_of(4),
+            "raw register offset out of the 4 KiB window or unaligned"
+        );
+        unsafe { self.read_register(offset) }
     }
 
-    /// Interrupt Mask Set/Clear Register, read/write, offset 0x38
-    pub fn read_interrupt_mask_set_clear_register(
-        &self,
-    ) -> registrers::InterruptMaskSetClearRegister {
-        unsafe { self.read_register(0x38) }
+    /// Writes `value` to the register at `offset` from the base address.
+    ///
+    /// This is what all the `write_*` methods are built on, see [`UART::read_register`].
+    /// The width of the access is the size of `R`.
+    ///
+    /// # Safety
+    /// `offset` must be a writable register of the peripheral that is suitably aligned for `R`,
+    /// and writing `value` to it must not break any invariants the rest of the program relies on.
+    pub unsafe fn write_register<R>(&self, offset: usize, value: R) {
+        unsafe { self.base.write(self.base.register_address(offset), value) }
     }
-    pub fn write_interrupt_mask_set_clear_register(
-        &self,
-        value: registrers::InterruptMaskSetClearRegister,
-    ) {
-        unsafe { self.write_register(0x38, value) }
+
+    /// Writes `value` as a 32-bit word to `offset` from the base address, see [`UART::read_raw_u32`].
+    ///
+    /// Nothing about the value is checked: reserved bits and reserved encodings are written as given, and a write to
+    /// the data register transmits a character like [`UART::write_data_register`] does. Writing to a register behind
+    /// the back of the rest of the driver can also break what it assumes, e.g. toggling FEN changes how
+    /// [`UART::write_bytes`] has to pace its writes.
+    ///
+    /// `offset` must be 4-byte aligned and inside the 4 KiB register window, which is checked in debug builds.
+    pub fn write_raw_u32(&self, offset: usize, value: u32) {
+        debug_assert!(
+            offset < offsets::REGISTER_WINDOW && offset.is_multiple_of(4),
+            "raw register offset out of the 4 KiB window or unaligned"
+        );
+        unsafe { self.write_register(offset, value) }
     }
-    pub fn update_interrupt_mask_set_clear_register<F>(&self, f: F)
+
+    /// Read-modify-write of the register at `offset` from the base address.
+    ///
+    /// This always performs a volatile read followed by a volatile write. The read can't be skipped
+    /// even if `f` ignores its argument, because the compiler isn't allowed to elide volatile accesses.
+    /// Callers that already know the full value should use [`UART::write_register`] instead.
+    ///
+    /// Only use this on registers that read back what was written. On write-only and write-to-clear registers such as
+    /// the interrupt clear register the read returns garbage, and on the data register it pops the receive FIFO.
+    ///
+    /// # Safety
+    /// The requirements of both [`UART::read_register`] and [`UART::write_register`] apply.
+    pub unsafe fn update_register<R, F>(&self, offset: usize, f: F)
     where
-        F: FnOnce(
-            registrers::InterruptMaskSetClearRegister,
-        ) -> registrers::InterruptMaskSetClearRegister,
+        F: FnOnce(R) -> R,
     {
-        unsafe { self.update_register(0x38, f) };
+        unsafe { self.write_register::<R>(offset, f(self.read_register::<R>(offset))) };
     }
 
-    /// Raw Interrupt Status Register, read-only, offset 0x3C
-    pub fn read_raw_interrupt_status_register(&self) -> registrers::RawInterruptStatusRegister {
-        unsafe { self.read_register(0x3C) }
+    /// data register, read/write, offset 0x00
+    ///
+    /// Only the low 8 bits are transmitted, the error flags are meaningless on a write.
+    /// In debug builds this panics if any of them are set, which usually means a received value is being sent back as is.
+    /// Use [`UART::write_data_checked`] to handle that case at runtime instead.
+    pub fn write_data_register(&self, value: registrers::DataRegister) {
+        debug_assert!(
+            value.bits() & !0xFF == 0,
+            "error flags set in a DataRegister written for transmission: {value:?}"
+        );
+        unsafe { self.write_register(offsets::DATA, value) }
     }
 
-    /// Masked Interrupt Status Register, read-only, offset 0x40
-    pub fn read_masked_interrupt_status_register(
+    /// Writes the data register, or returns `value` without writing it if any of its error flags are set.
+    pub fn write_data_checked(
         &self,
-    ) -> registrers::MaskedInterruptStatusRegister {
-        unsafe { self.read_register(0x40) }
+        value: registrers::DataRegister,
+    ) -> Result<(), registrers::DataRegister> {
+        if value.bits() & !0xFF != 0 {
+            return Err(value);
+        }
+        unsafe { self.write_register(offsets::DATA, value) };
+        Ok(())
     }
+}
 
-    /// Interrupt Clear Register, write-only, offset 0x44
-    pub fn write_interrupt_clear_register(&self, value: registrers::InterruptClearRegister) {
-        unsafe { self.write_register(0x44, value) }
-    }
+/// Errors returned by [`UART::from_device_tree_reg`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DtError {
+    /// The size of the `reg` entry doesn't cover the 4 KiB register window of the peripheral.
+    WindowTooSmall,
+    /// The base address isn't word (4-byte) aligned.
+    Misaligned,
+    /// The register window doesn't fit in the address space of the target.
+    AddressOutOfRange,
+}
 
-    /// DMA Control Register, read/write, offset 0x48
-    pub fn read_dma_control_register(&self) -> registrers::DMAControlRegister {
-        unsafe { self.read_register(0x48) }
-    }
-    pub fn write_dma_control_register(&self, value: registrers::DMAControlRegister) {
-        unsafe { self.write_register(0x48, value) }
-    }
-    pub fn update_dma_control_register<F>(&self, f: F)
-    where
-        F: FnOnce(registrers::DMAControlRegister) -> registrers::DMAControlRegister,
-    {
-        unsafe { self.update_register(0x48, f) };
+impl UART<usize> {
+    /// Creates a handle from a device-tree style `reg` tuple of `(base, size)`.
+    ///
+    /// The cells are taken as 64-bit values, so they can be passed straight from a device-tree parser.
+    /// This checks that `size` covers the whole register window, that `base` is word aligned
+    /// and that the window is addressable on this target.
+    pub fn from_device_tree_reg(base: u64, size: u64) -> Result<Self, DtError> {
+        if size < offsets::REGISTER_WINDOW as u64 {
+            return Err(DtError::WindowTooSmall);
+        }
+        if !base.is_multiple_of(4) {
+            return Err(DtError::Misaligned);
+        }
+        let last = base
+            .checked_add(offsets::REGISTER_WINDOW as u64 - 1)
+            .ok_or(DtError::AddressOutOfRange)?;
+        usize::try_from(last).map_err(|_| DtError::AddressOutOfRange)?;
+        let base = usize::try_from(base).map_err(|_| DtError::AddressOutOfRange)?;
+        Ok(UART::new(base))
     }
 }
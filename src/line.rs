@@ -0,0 +1,80 @@
+//! A blocking line reader for serial consoles and monitors.
+use crate::{BaseAddress, UART, blocking::ReceiveError};
+
+/// Why [`UART::read_line`] stopped before a complete line was read.
+///
+/// In every case the bytes received so far stay in the buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LineError {
+    /// A character was received with an error.
+    Receive(ReceiveError),
+    /// A byte outside of the ASCII range was received.
+    NonAscii(u8),
+    /// The buffer filled up before a line terminator was received.
+    BufferFull,
+}
+
+/// ASCII backspace
+const BS: u8 = 0x08;
+/// ASCII delete, which most terminals send for the backspace key
+const DEL: u8 = 0x7F;
+
+impl From<ReceiveError> for LineError {
+    fn from(error: ReceiveError) -> Self {
+        LineError::Receive(error)
+    }
+}
+
+impl<T: BaseAddress> UART<T> {
+    /// Reads a line of ASCII text into `buf`, which is cleared first.
+    ///
+    /// This busy-waits on the receive FIFO and only returns once a line terminator has been received,
+    /// the buffer is full or a receive error occurred, so it can block forever on an idle line.
+    ///
+    /// A line ends on `\n`, which isn't stored, so an empty line is returned as soon as a bare `\n` is
+    /// received. `\r` is dropped wherever it appears, which makes `\r\n` terminated input read the same
+    /// as `\n` terminated input. A terminal that only sends `\r` for the enter key needs its newline
+    /// translation turned on (`--omap crlf` for picocom, "Add linefeed" for minicom).
+    /// Backspace and delete remove the last character from the buffer.
+    /// Received characters are not echoed back.
+    ///
+    /// ```
+    /// # #[cfg(feature = "test-util")] {
+    /// use pl011_uart_registers::testing::MockUart;
+    ///
+    /// let mock = MockUart::new();
+    /// let uart = mock.uart();
+    /// let mut line = heapless::String::<16>::new();
+    ///
+    /// mock.push_received(b"\nhelp\r\n\r\nab\x7fc\n");
+    /// uart.read_line(&mut line).unwrap();
+    /// assert_eq!(line, "");
+    /// uart.read_line(&mut line).unwrap();
+    /// assert_eq!(line, "help");
+    /// uart.read_line(&mut line).unwrap();
+    /// assert_eq!(line, "");
+    /// uart.read_line(&mut line).unwrap();
+    /// assert_eq!(line, "ac");
+    /// # }
+    /// ```
+    pub fn read_line<const N: usize>(
+        &self,
+        buf: &mut heapless::String<N>,
+    ) -> Result<(), LineError> {
+        buf.clear();
+        loop {
+            match self.read_byte()? {
+                b'\n' => return Ok(()),
+                b'\r' => {}
+                BS | DEL => {
+                    buf.pop();
+                }
+                byte if byte.is_ascii() => {
+                    buf.push(byte as char).map_err(|_| LineError::BufferFull)?;
+                }
+                byte => return Err(LineError::NonAscii(byte)),
+            }
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! The macro that generates the typed accessors on [`UART`](crate::UART) from a list of registers.
+
+/// Generates the `read_*`, `write_*`, `update_*` and pointer accessors of [`UART`](crate::UART).
+///
+/// Every entry is an offset from [`offsets`](crate::offsets), the register type in [`registrers`](crate::registrers) and the methods to generate for it:
+///
+/// ```ignore
+/// register_block! {
+///     offsets::FLAG => FlagRegister {
+///         /// Flag register, read-only, offset 0x18
+///         read: read_flag_register,
+///         /// Pointer to the Flag Register, offset 0x18.
+///         ptr: flag_register_ptr,
+///     },
+/// }
+/// ```
+///
+/// - `read` generates `fn(&self) -> Register` for any access mode, the register types are `#[must_use]` themselves,
+/// - `pop` generates the same on read/write handles only, for registers whose reads have side effects: reading the
+///   data registers pops a FIFO, which a [`ReadOnly`](crate::access::ReadOnly) handle must not be able to do,
+/// - `write` generates `fn(&self, Register)` on read/write handles,
+/// - `update` generates a read-modify-write `fn(&self, impl FnOnce(Register) -> Register)` on read/write handles,
+///   only allowed on registers that also have `read` and `write`,
+/// - `clear` generates `fn(&self)` that writes the register's default (all zero) value, for write-to-clear registers,
+/// - `ptr` generates `fn(&self) -> *mut Register`.
+///
+/// Every register type also gets an `OFFSET` constant.
+/// Doc comments on a method are copied onto it, `update` gets a standard one if it has none.
+/// The build fails if two registers at the same offset can both be read, or can both be written.
+/// It also fails if a register has `update` without both `read` and `write`: a read-modify-write only makes sense
+/// when reading gives back what was written, so write-only and write-to-clear registers never get one.
+/// Neither do the `pop` registers, since reading them pops a FIFO instead.
+macro_rules! register_block {
+    (
+        $(
+            $offset:expr => $register:ident {
+                $( $(#[$meta:meta])* $kind:ident: $method:ident ),* $(,)?
+            }
+        ),* $(,)?
+    ) => {
+        impl<T: BaseAddress, A> UART<T, A> {
+            $( $( register_block!(@read $kind [$(#[$meta])*] $method $offset $register); )* )*
+        }
+
+        impl<T: BaseAddress> UART<T> {
+            $( $( register_block!(@pop $kind [$(#[$meta])*] $method $offset $register); )* )*
+            $( $( register_block!(@write $kind [$(#[$meta])*] $method $offset $register); )* )*
+        }
+
+        /// Raw pointers to the individual registers.
+        ///
+        /// These are an escape hatch for access patterns the typed methods don't cover,
+        /// e.g. wrapping a register in a `VolatileCell` or performing the access with a specific instruction.
+        /// The pointers are computed from the base address and are only valid as long as the base address is.
+        ///
+        /// Dereferencing any of them is `unsafe`: the access must be volatile, must use the width of the pointee type,
+        /// and must respect the access mode of the register (don't read write-only registers or write read-only ones).
+        /// Keep in mind that reading the data register pops the receive FIFO.
+        impl<T: BaseAddress> UART<T> {
+            $( $( register_block!(@ptr $kind [$(#[$meta])*] $method $offset $register); )* )*
+        }
+
+        $(
+            impl registrers::$register {
+                /// The offset of this register from the base address.
+                pub const OFFSET: usize = $offset;
+            }
+        )*
+
+        const _: () = {
+            $( $( register_block!(@kind $kind); )* )*
+            // (offset, readable, poppable, writable, updatable) of every register
+            let registers = [$(
+                (
+                    $offset,
+                    register_block!(@readable $($kind)*),
+                    register_block!(@poppable $($kind)*),
+                    register_block!(@writable $($kind)*),
+                    register_block!(@updatable $($kind)*),
+                )
+            ),*];
+            let mut i = 0;
+            while i < registers.len() {
+                let (_, read, pop, write, update) = registers[i];
+                if update && pop {
+                    panic!("`update` can't be combined with `pop`, reading the register has side effects");
+                }
+                if update && !(read && write) {
+                    panic!("`update` needs both `read` and `write` on the same register");
+                }
+                let mut j = i + 1;
+                while j < registers.len() {
+                    let (offset_a, read_a, pop_a, write_a, _) = registers[i];
+                    let (offset_b, read_b, pop_b, write_b, _) = registers[j];
+                    let (read_a, read_b) = (read_a || pop_a, read_b || pop_b);
+                    if offset_a == offset_b && ((read_a && read_b) || (write_a && write_b)) {
+                        panic!("two registers at the same offset have overlapping access");
+                    }
+                    j += 1;
+                }
+                i += 1;
+            }
+        };
+    };
+
+    (@read read [$(#[$meta:meta])*] $method:ident $offset:tt $register:ident) => {
+        $(#[$meta])*
+        pub fn $method(&self) -> registrers::$register {
+            unsafe { self.read_register($offset) }
+        }
+    };
+    (@read $kind:ident $($rest:tt)*) => {};
+
+    (@pop pop [$(#[$meta:meta])*] $method:ident $offset:tt $register:ident) => {
+        $(#[$meta])*
+        pub fn $method(&self) -> registrers::$register {
+            unsafe { self.read_register($offset) }
+        }
+    };
+    (@pop $kind:ident $($rest:tt)*) => {};
+
+    (@write write [$(#[$meta:meta])*] $method:ident $offset:tt $register:ident) => {
+        $(#[$meta])*
+        pub fn $method(&self, value: registrers::$register) {
+            unsafe { self.write_register($offset, value) }
+        }
+    };
+    (@write update [] $method:ident $offset:tt $register:ident) => {
+        register_block!(
+            @write update [
+                /// Read-modify-write of the register: reads it, passes the value to `f` and writes back the result.
+                ///
+                /// This costs a bus read on top of the write. When the complete new value is known up front, use the matching `write_*` method instead.
+            ] $method $offset $register
+        );
+    };
+    (@write update [$(#[$meta:meta])+] $method:ident $offset:tt $register:ident) => {
+        $(#[$meta])+
+        pub fn $method<F>(&self, f: F)
+        where
+            F: FnOnce(registrers::$register) -> registrers::$register,
+        {
+            unsafe { self.update_register($offset, f) };
+        }
+    };
+    (@write clear [$(#[$meta:meta])*] $method:ident $offset:tt $register:ident) => {
+        $(#[$meta])*
+        pub fn $method(&self) {
+            unsafe { self.write_register($offset, <registrers::$register>::default()) }
+        }
+    };
+    (@write $kind:ident $($rest:tt)*) => {};
+
+    (@ptr ptr [$(#[$meta:meta])*] $method:ident $offset:tt $register:ident) => {
+        $(#[$meta])*
+        pub fn $method(&self) -> *mut registrers::$register {
+            self.register_ptr($offset)
+        }
+    };
+    (@ptr $kind:ident $($rest:tt)*) => {};
+
+    (@kind read) => {};
+    (@kind pop) => {};
+    (@kind write) => {};
+    (@kind update) => {};
+    (@kind clear) => {};
+    (@kind ptr) => {};
+    (@kind $kind:ident) => {
+        compile_error!(concat!(
+            "unknown register method kind `",
+            stringify!($kind),
+            "`, expected one of read, pop, write, update, clear or ptr"
+        ));
+    };
+
+    (@readable) => { false };
+    (@readable read $($rest:ident)*) => { true };
+    (@readable $kind:ident $($rest:ident)*) => { register_block!(@readable $($rest)*) };
+
+    (@poppable) => { false };
+    (@poppable pop $($rest:ident)*) => { true };
+    (@poppable $kind:ident $($rest:ident)*) => { register_block!(@poppable $($rest)*) };
+
+    (@writable) => { false };
+    (@writable write $($rest:ident)*) => { true };
+    (@writable clear $($rest:ident)*) => { true };
+    (@writable $kind:ident $($rest:ident)*) => { register_block!(@writable $($rest)*) };
+
+    (@updatable) => { false };
+    (@updatable update $($rest:ident)*) => { true };
+    (@updatable $kind:ident $($rest:ident)*) => { register_block!(@updatable $($rest)*) };
+}
@@ -0,0 +1,135 @@
+//! The conventional `"8N1"` notation for the serial format.
+//!
+//! A mode string is the number of data bits (5 to 8), a parity letter and the number of stop bits (1 or 2).
+//! The parity letters are `N` (none), `O` (odd), `E` (even), `M` (mark, always 1) and `S` (space, always 0).
+//! Mark and space parity map onto the stick parity modes [`Parity::StickHigh`] and [`Parity::StickLow`].
+//!
+//! ```
+//! use pl011_uart_registers::{
+//!     mode::{ModeError, parse_mode, to_mode_string},
+//!     registrers::{LineControlRegister, Parity, StopBits, WordLength},
+//! };
+//!
+//! assert_eq!(parse_mode("8N1"), Ok((WordLength::EightBits, Parity::None, StopBits::One)));
+//! assert_eq!(parse_mode("7e2"), Ok((WordLength::SevenBits, Parity::Even, StopBits::Two)));
+//! assert_eq!(parse_mode("8M1").map(|(_, parity, _)| parity), Ok(Parity::StickHigh));
+//! assert_eq!(parse_mode("8s1").map(|(_, parity, _)| parity), Ok(Parity::StickLow));
+//!
+//! assert_eq!(parse_mode("9N1"), Err(ModeError::DataBits));
+//! assert_eq!(parse_mode("4N1"), Err(ModeError::DataBits));
+//! assert_eq!(parse_mode("xN1"), Err(ModeError::DataBits));
+//! assert_eq!(parse_mode("8X1"), Err(ModeError::Parity));
+//! assert_eq!(parse_mode("8N3"), Err(ModeError::StopBits));
+//! assert_eq!(parse_mode("8N"), Err(ModeError::Length));
+//!
+//! // every format round-trips, with the parity letter coming back uppercase
+//! for &word_length in WordLength::all() {
+//!     for &parity in Parity::all() {
+//!         for stop_bits in [StopBits::One, StopBits::Two] {
+//!             let mode = to_mode_string(word_length, parity, stop_bits);
+//!             assert_eq!(parse_mode(mode.as_str()), Ok((word_length, parity, stop_bits)));
+//!             let lcr = LineControlRegister::from_mode(mode.as_str()).unwrap();
+//!             assert_eq!(lcr.to_mode_string(), mode);
+//!         }
+//!     }
+//! }
+//! assert_eq!(to_mode_string(WordLength::SevenBits, Parity::StickLow, StopBits::Two).as_str(), "7S2");
+//! ```
+use core::fmt;
+
+use crate::registrers::{LineControlRegister, Parity, StopBits, WordLength};
+
+/// Why a mode string couldn't be parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ModeError {
+    /// The string isn't exactly three characters long.
+    Length,
+    /// The first character isn't a number of data bits from 5 to 8.
+    DataBits,
+    /// The second character isn't one of the parity letters `N`, `O`, `E`, `M` or `S`.
+    Parity,
+    /// The third character isn't a number of stop bits, 1 or 2.
+    StopBits,
+}
+
+/// Parses a mode string such as `"8N1"` or `"7E2"`. The parity letter may be lowercase.
+pub fn parse_mode(mode: &str) -> Result<(WordLength, Parity, StopBits), ModeError> {
+    let &[data_bits, parity, stop_bits] = mode.as_bytes() else {
+        return Err(ModeError::Length);
+    };
+    let word_length = match data_bits {
+        b'0'..=b'9' => WordLength::from_data_bits(data_bits - b'0').ok_or(ModeError::DataBits)?,
+        _ => return Err(ModeError::DataBits),
+    };
+    let parity = match parity.to_ascii_uppercase() {
+        b'N' => Parity::None,
+        b'O' => Parity::Odd,
+        b'E' => Parity::Even,
+        b'M' => Parity::StickHigh,
+        b'S' => Parity::StickLow,
+        _ => return Err(ModeError::Parity),
+    };
+    let stop_bits = match stop_bits {
+        b'1' => StopBits::One,
+        b'2' => StopBits::Two,
+        _ => return Err(ModeError::StopBits),
+    };
+    Ok((word_length, parity, stop_bits))
+}
+
+/// Formats the serial format as a mode string, with an uppercase parity letter.
+pub fn to_mode_string(word_length: WordLength, parity: Parity, stop_bits: StopBits) -> ModeString {
+    let parity = match parity {
+        Parity::None => b'N',
+        Parity::Odd => b'O',
+        Parity::Even => b'E',
+        Parity::StickHigh => b'M',
+        Parity::StickLow => b'S',
+    };
+    let stop_bits = match stop_bits {
+        StopBits::One => b'1',
+        StopBits::Two => b'2',
+    };
+    ModeString([b'0' + word_length.data_bits(), parity, stop_bits])
+}
+
+/// A three character mode string such as `"8N1"`, returned by [`to_mode_string`].
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ModeString([u8; 3]);
+
+impl ModeString {
+    pub fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.0).expect("mode strings are ASCII")
+    }
+}
+
+impl fmt::Display for ModeString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for ModeString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl LineControlRegister {
+    /// Builds a line control register with the word length, parity and stop bits of a mode string like `"8N1"`.
+    ///
+    /// Every other bit is cleared, in particular the FIFOs are disabled.
+    pub fn from_mode(mode: &str) -> Result<Self, ModeError> {
+        let (word_length, parity, stop_bits) = parse_mode(mode)?;
+        Ok(LineControlRegister::from_bits(0)
+            .with_word_length(word_length)
+            .with_parity(parity)
+            .with_stop_bits(stop_bits))
+    }
+
+    /// The word length, parity and stop bits of this register as a mode string like `"8N1"`.
+    pub fn to_mode_string(&self) -> ModeString {
+        to_mode_string(self.word_length(), self.parity(), self.stop_bits())
+    }
+}
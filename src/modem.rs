@@ -0,0 +1,263 @@
+//! Helpers for the modem control outputs of the UART.
+//!
+//! The modem outputs (nUARTDTR, nUARTRTS, nUARTOut1, nUARTOut2) are active-low signals.
+//! The corresponding bits in the control register are the complement of the pin level,
+//! so programming a bit to `true` drives the pin LOW, which asserts the signal.
+//!
+//! Hardware flow control takes over two of these signals. With RTS flow control enabled the UART drives nUARTRTS
+//! itself: it's asserted until the receive FIFO fills up to the receive trigger level set in the interrupt FIFO level
+//! select register, and deasserted from then on until the FIFO is read below it. The `request_to_send` bit in the
+//! control register has no effect on the pin while this is on. With CTS flow control enabled the transmitter only
+//! starts a character while nUARTCTS is asserted. nUARTDTR, nUARTOut1 and nUARTOut2 are never touched by flow control.
+//!
+//! An RS-485 driver that uses nUARTRTS as the driver enable therefore has to leave RTS flow control off,
+//! see [`UART::transmit_half_duplex`].
+use crate::{
+    BaseAddress, UART,
+    registrers::{ControlRegister, FlagRegister},
+};
+
+/// One of the modem control outputs of the UART.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModemLine {
+    /// nUARTDTR, looped back to nUARTDSR.
+    DataTerminalReady,
+    /// nUARTRTS, looped back to nUARTCTS.
+    RequestToSend,
+    /// nUARTOut1, looped back to nUARTDCD.
+    Out1,
+    /// nUARTOut2, looped back to nUARTRI.
+    Out2,
+}
+
+/// Hardware flow control, as set in a [`UartConfig`](crate::config::UartConfig).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FlowControl {
+    /// No flow control, nUARTRTS is driven from the control register and nUARTCTS is ignored.
+    #[default]
+    None,
+    /// RTS and CTS hardware flow control, see the [module documentation](self) for how the signals behave.
+    RtsCts,
+}
+
+impl ModemLine {
+    const ALL: [ModemLine; 4] = [
+        ModemLine::DataTerminalReady,
+        ModemLine::RequestToSend,
+        ModemLine::Out1,
+        ModemLine::Out2,
+    ];
+}
+
+/// The state of the four modem status inputs, decoded from the [`FlagRegister`] by [`FlagRegister::modem_status`].
+///
+/// The inputs (nUARTCTS, nUARTDSR, nUARTDCD, nUARTRI) are active-low, and the flag register bits are the complement
+/// of the pin level. A bit reading as 1 therefore means the pin is LOW, which means the signal is asserted.
+/// The fields here are named for that asserted condition, so `true` always means asserted, whatever the pin level.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ModemStatus {
+    /// Clear to send, the nUARTCTS pin is LOW.
+    pub cts_asserted: bool,
+    /// Data set ready, the nUARTDSR pin is LOW.
+    pub dsr_asserted: bool,
+    /// Data carrier detect, the nUARTDCD pin is LOW.
+    pub dcd_asserted: bool,
+    /// Ring indicator, the nUARTRI pin is LOW.
+    pub ri_asserted: bool,
+}
+
+impl FlagRegister {
+    /// The state of the modem status inputs, see [`ModemStatus`] for how the bits map onto the pins.
+    pub fn modem_status(&self) -> ModemStatus {
+        ModemStatus {
+            cts_asserted: self.clear_to_send(),
+            dsr_asserted: self.data_set_ready(),
+            dcd_asserted: self.data_carrier_detect(),
+            ri_asserted: self.ring_indicator(),
+        }
+    }
+}
+
+impl ControlRegister {
+    /// Returns [`FlowControl::RtsCts`] if both RTS and CTS hardware flow control are enabled.
+    ///
+    /// If only one of them is enabled this returns [`FlowControl::None`].
+    pub fn flow_control(&self) -> FlowControl {
+        if self.rts_hardware_flow_control_enable() && self.cts_hardware_flow_control_enable() {
+            FlowControl::RtsCts
+        } else {
+            FlowControl::None
+        }
+    }
+
+    /// Enables both RTS and CTS hardware flow control for [`FlowControl::RtsCts`], and disables both otherwise.
+    pub fn with_flow_control(self, flow_control: FlowControl) -> Self {
+        let enable = flow_control == FlowControl::RtsCts;
+        self.with_rts_hardware_flow_control_enable(enable)
+            .with_cts_hardware_flow_control_enable(enable)
+    }
+
+    /// Returns whether the modem output `line` is asserted, i.e. whether its pin is driven LOW.
+    pub fn modem_line(&self, line: ModemLine) -> bool {
+        match line {
+            ModemLine::DataTerminalReady => self.data_transmit_ready(),
+            ModemLine::RequestToSend => self.request_to_send(),
+            ModemLine::Out1 => self.out1(),
+            ModemLine::Out2 => self.out2(),
+        }
+    }
+
+    /// Asserts the modem output `line` if `asserted` is `true`, driving its pin LOW, and deasserts it otherwise.
+    ///
+    /// The control register bits are the complement of the active-low pins, so asserting a line sets its bit.
+    pub fn with_modem_line(self, line: ModemLine, asserted: bool) -> Self {
+        match line {
+            ModemLine::DataTerminalReady => self.with_data_transmit_ready(asserted),
+            ModemLine::RequestToSend => self.with_request_to_send(asserted),
+            ModemLine::Out1 => self.with_out1(asserted),
+            ModemLine::Out2 => self.with_out2(asserted),
+        }
+    }
+}
+
+/// Returned by [`UART::check_modem_loopback`] when a modem input did not follow its output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModemLoopbackError {
+    /// The output that was driven.
+    pub line: ModemLine,
+    /// Whether the output was being asserted (`true`) or deasserted (`false`) when the mismatch was seen.
+    pub asserted: bool,
+}
+
+impl<T: BaseAddress, A> UART<T, A> {
+    /// Reads the modem status inputs, see [`FlagRegister::modem_status`].
+    pub fn modem_status(&self) -> ModemStatus {
+        self.read_flag_register().modem_status()
+    }
+
+    /// Returns `true` if both RTS and CTS hardware flow control are enabled.
+    pub fn hardware_flow_control(&self) -> bool {
+        self.read_control_register().flow_control() == FlowControl::RtsCts
+    }
+}
+
+impl<T: BaseAddress> UART<T> {
+    /// Sets the nUARTOut1 modem output using a read-modify-write of the control register.
+    ///
+    /// `true` asserts the output, which drives the nUARTOut1 pin LOW. `false` drives it HIGH.
+    ///
+    /// For DTE this line is conventionally used as Data Carrier Detect (DCD),
+    /// but it can be repurposed as a general-purpose output.
+    pub fn set_out1(&self, asserted: bool) {
+        self.update_control_register(|cr| cr.with_out1(asserted));
+    }
+
+    /// Sets the nUARTOut2 modem output using a read-modify-write of the control register.
+    ///
+    /// `true` asserts the output, which drives the nUARTOut2 pin LOW. `false` drives it HIGH.
+    ///
+    /// For DTE this line is conventionally used as Ring Indicator (RI),
+    /// but it can be repurposed as a general-purpose output.
+    pub fn set_out2(&self, asserted: bool) {
+        self.update_control_register(|cr| cr.with_out2(asserted));
+    }
+
+    /// Sets the nUARTDTR (data terminal ready) modem output using a read-modify-write of the control register.
+    ///
+    /// `true` asserts DTR, which drives the nUARTDTR pin LOW. `false` deasserts it.
+    pub fn set_dtr(&self, asserted: bool) {
+        self.update_control_register(|cr| cr.with_data_transmit_ready(asserted));
+    }
+
+    /// Sets the nUARTRTS (request to send) modem output using a read-modify-write of the control register.
+    ///
+    /// `true` asserts RTS, which drives the nUARTRTS pin LOW. `false` deasserts it.
+    ///
+    /// Note that when RTS hardware flow control is enabled the UART drives this line itself.
+    pub fn set_rts(&self, asserted: bool) {
+        self.update_control_register(|cr| cr.with_request_to_send(asserted));
+    }
+
+    /// Enables or disables RTS and CTS hardware flow control together, using a read-modify-write of the control register.
+    ///
+    /// While enabled the UART drives nUARTRTS itself, see the [module documentation](self).
+    pub fn set_hardware_flow_control(&self, enabled: bool) {
+        let flow_control = if enabled {
+            FlowControl::RtsCts
+        } else {
+            FlowControl::None
+        };
+        self.update_control_register(|cr| cr.with_flow_control(flow_control));
+    }
+
+    /// Asserts or deasserts the modem output `line` using a read-modify-write of the control register.
+    ///
+    /// See [`ControlRegister::with_modem_line`].
+    pub fn set_modem_line(&self, line: ModemLine, asserted: bool) {
+        self.update_control_register(|cr| cr.with_modem_line(line, asserted));
+    }
+
+    /// Sends `data` over a half-duplex link such as RS-485, with `de_line` as the transceiver's driver enable.
+    ///
+    /// `de_line` is asserted (its pin driven LOW), all of `data` is written, and the line is deasserted again once
+    /// the UART is no longer busy. If the transceiver enables its driver on a HIGH level, put an inverter in between
+    /// or wire the enable to the opposite level of the receiver enable.
+    ///
+    /// The driver must stay enabled until the stop bits of the last character have left the transmitter, or that
+    /// character is cut off on the bus. The transmit FIFO empty flag is set as soon as the last character moves into
+    /// the shift register, one full character time too early, so this waits for BUSY to clear with [`UART::flush`]
+    /// instead. Anything that delays the deassertion (an interrupt handler running in between) keeps the bus driven
+    /// for longer, so the other side should leave some turnaround time before replying.
+    ///
+    /// If the receiver enable isn't tied to the inverse of the driver enable, the UART receives its own transmission,
+    /// and the echoed characters end up in the receive FIFO. When `de_line` is [`ModemLine::RequestToSend`],
+    /// RTS hardware flow control must be disabled, as it would otherwise drive the pin itself.
+    pub fn transmit_half_duplex(&self, data: &[u8], de_line: ModemLine) {
+        self.set_modem_line(de_line, true);
+        self.write_bytes(data);
+        self.flush();
+        self.set_modem_line(de_line, false);
+    }
+
+    /// reads back the flag register input that `line` is fed into while in loopback mode
+    fn looped_back_input(&self, line: ModemLine) -> bool {
+        let status = self.modem_status();
+        match line {
+            ModemLine::DataTerminalReady => status.dsr_asserted,
+            ModemLine::RequestToSend => status.cts_asserted,
+            ModemLine::Out1 => status.dcd_asserted,
+            ModemLine::Out2 => status.ri_asserted,
+        }
+    }
+
+    /// Verifies the modem signal wiring using loopback mode.
+    ///
+    /// In loopback mode the modem outputs are fed through to the modem inputs:
+    /// nUARTDTR to nUARTDSR, nUARTRTS to nUARTCTS, nUARTOut1 to nUARTDCD and nUARTOut2 to nUARTRI.
+    /// This asserts and deasserts every output in turn and checks that the matching flag register bit follows it.
+    /// Because both the control and the flag register bits are the complement of the pin level, an asserted output must read back as `true`.
+    ///
+    /// Loopback is enabled and hardware flow control is disabled for the duration of the check,
+    /// and the control register is restored afterwards. The UART should be idle while this runs.
+    pub fn check_modem_loopback(&self) -> Result<(), ModemLoopbackError> {
+        let saved = self.read_control_register();
+        self.write_control_register(
+            saved
+                .with_loopback_enable(true)
+                .with_cts_hardware_flow_control_enable(false)
+                .with_rts_hardware_flow_control_enable(false),
+        );
+        let result = ModemLine::ALL.into_iter().try_for_each(|line| {
+            [true, false].into_iter().try_for_each(|asserted| {
+                self.set_modem_line(line, asserted);
+                if self.looped_back_input(line) == asserted {
+                    Ok(())
+                } else {
+                    Err(ModemLoopbackError { line, asserted })
+                }
+            })
+        });
+        self.write_control_register(saved);
+        result
+    }
+}
@@ -0,0 +1,50 @@
+//! Non-blocking `embedded-hal-nb` serial traits, enabled by the `embedded-hal` feature.
+use crate::{BaseAddress, UART, blocking::ReceiveError, registrers::DataRegister};
+use embedded_hal_nb::{nb, serial};
+
+impl serial::Error for ReceiveError {
+    fn kind(&self) -> serial::ErrorKind {
+        match self {
+            ReceiveError::Overrun => serial::ErrorKind::Overrun,
+            ReceiveError::Parity => serial::ErrorKind::Parity,
+            ReceiveError::Framing => serial::ErrorKind::FrameFormat,
+            ReceiveError::Break => serial::ErrorKind::Other,
+        }
+    }
+}
+
+impl<T: BaseAddress> serial::ErrorType for UART<T> {
+    type Error = ReceiveError;
+}
+
+impl<T: BaseAddress> serial::Read<u8> for UART<T> {
+    /// Returns `WouldBlock` while the receive FIFO is empty. A character received with an error is consumed
+    /// and reported as that error.
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        match self.try_read_byte() {
+            None => Err(nb::Error::WouldBlock),
+            Some(result) => result.map_err(nb::Error::Other),
+        }
+    }
+}
+
+impl<T: BaseAddress> serial::Write<u8> for UART<T> {
+    /// Returns `WouldBlock` while the transmit FIFO is full.
+    fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+        if self.read_flag_register().transmit_fifo_full() {
+            return Err(nb::Error::WouldBlock);
+        }
+        self.write_data_register(DataRegister::default().with_data(word));
+        Ok(())
+    }
+
+    /// Returns `WouldBlock` until the transmit FIFO is empty and the last character has been shifted out.
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        let flags = self.read_flag_register();
+        if flags.is_idle() {
+            Ok(())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+}
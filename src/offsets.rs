@@ -0,0 +1,172 @@
+//! The offset of every register from the base address, as listed in the TRM.
+//!
+//! All the accessors on [`UART`](crate::UART) and [`Registers`](crate::access::Registers) use these constants,
+//! and every register type has its offset as `OFFSET` too. They're meant for access patterns this crate doesn't
+//! cover, e.g. a DMA descriptor that needs the address of the data register:
+//!
+//! ```
+//! use pl011_uart_registers::{UART, offsets, registrers::FlagRegister};
+//!
+//! let uart = UART::new(0x0900_0000_usize);
+//! assert_eq!(uart.flag_register_ptr() as usize, 0x0900_0000 + offsets::FLAG);
+//! assert_eq!(FlagRegister::OFFSET, offsets::FLAG);
+//! ```
+//!
+//! Every accessor is checked against its constant here, by recording the address it touches:
+//!
+//! ```
+//! # use core::cell::Cell;
+//! # use pl011_uart_registers::{BaseAddress, MmioAccess, UART, offsets::*, registrers::*};
+//! #[derive(Default)]
+//! struct Recorder(Cell<usize>);
+//!
+//! impl BaseAddress for &Recorder {
+//!     fn base_address(self) -> usize {
+//!         0
+//!     }
+//! }
+//!
+//! impl MmioAccess for &Recorder {
+//!     unsafe fn read<R>(&self, addr: usize) -> R {
+//!         self.0.set(addr);
+//!         unsafe { core::mem::zeroed() }
+//!     }
+//!     unsafe fn write<R>(&self, addr: usize, _value: R) {
+//!         self.0.set(addr);
+//!     }
+//! }
+//!
+//! let recorder = Recorder::default();
+//! let uart = UART::new(&recorder);
+//! let regs = uart.registers();
+//! macro_rules! check {
+//!     ($offset:expr, $($access:expr),+) => {
+//!         $(
+//!             recorder.0.set(usize::MAX);
+//!             let _ = $access;
+//!             assert_eq!(recorder.0.get(), $offset, "{}", stringify!($access));
+//!         )+
+//!     };
+//! }
+//! check!(DATA, uart.read_data_register(), uart.write_data_register(DataRegister::default()), uart.read_data_raw());
+//! check!(DATA, regs.data.read());
+//! check!(RECEIVE_STATUS, uart.read_receive_status_register(), regs.receive_status.read());
+//! check!(ERROR_CLEAR, uart.write_error_clear_register(), regs.error_clear.write(ErrorClearRegister::default()));
+//! check!(FLAG, uart.read_flag_register(), regs.flag.read());
+//! check!(IRDA_LOW_POWER, uart.read_irda_low_power_register(), uart.write_irda_low_power_register(Default::default()));
+//! check!(IRDA_LOW_POWER, regs.irda_low_power.read());
+//! check!(INTEGER_BAUD_RATE_DIVISOR, uart.read_integer_baud_rate_divisor_register());
+//! check!(INTEGER_BAUD_RATE_DIVISOR, uart.write_integer_baud_rate_divisor_register(Default::default()));
+//! check!(INTEGER_BAUD_RATE_DIVISOR, regs.integer_baud_rate_divisor.read());
+//! check!(FRACTIONAL_BAUD_RATE_DIVISOR, uart.read_fractional_baud_rate_divisor_register());
+//! check!(FRACTIONAL_BAUD_RATE_DIVISOR, uart.write_fractional_baud_rate_divisor_register(Default::default()));
+//! check!(FRACTIONAL_BAUD_RATE_DIVISOR, regs.fractional_baud_rate_divisor.read());
+//! check!(LINE_CONTROL, uart.read_line_control_register(), uart.write_line_control_register(Default::default()));
+//! check!(LINE_CONTROL, regs.line_control.read());
+//! check!(CONTROL, uart.read_control_register(), uart.write_control_register(Default::default()), regs.control.read());
+//! check!(INTERRUPT_FIFO_LEVEL_SELECT, uart.read_interrupt_fifo_level_select_register());
+//! check!(INTERRUPT_FIFO_LEVEL_SELECT, uart.write_interrupt_fifo_level_select_register(Default::default()));
+//! check!(INTERRUPT_FIFO_LEVEL_SELECT, regs.interrupt_fifo_level_select.read());
+//! check!(INTERRUPT_MASK_SET_CLEAR, uart.read_interrupt_mask_set_clear_register());
+//! check!(INTERRUPT_MASK_SET_CLEAR, uart.write_interrupt_mask_set_clear_register(InterruptMaskSetClearRegister::from_bits(0)));
+//! check!(INTERRUPT_MASK_SET_CLEAR, regs.interrupt_mask_set_clear.read());
+//! check!(RAW_INTERRUPT_STATUS, uart.read_raw_interrupt_status_register(), regs.raw_interrupt_status.read());
+//! check!(MASKED_INTERRUPT_STATUS, uart.read_masked_interrupt_status_register(), regs.masked_interrupt_status.read());
+//! check!(INTERRUPT_CLEAR, uart.write_interrupt_clear_register(Default::default()));
+//! check!(INTERRUPT_CLEAR, regs.interrupt_clear.write(Default::default()));
+//! check!(DMA_CONTROL, uart.read_dma_control_register(), uart.write_dma_control_register(Default::default()));
+//! check!(DMA_CONTROL, regs.dma_control.read());
+//! check!(TEST_CONTROL, uart.read_test_control_register(), uart.write_test_control_register(Default::default()));
+//! check!(TEST_CONTROL, regs.test_control.read());
+//! check!(INTEGRATION_TEST_INPUT, uart.read_integration_test_input_register(), regs.integration_test_input.read());
+//! check!(INTEGRATION_TEST_OUTPUT, uart.read_integration_test_output_register());
+//! check!(INTEGRATION_TEST_OUTPUT, uart.write_integration_test_output_register(Default::default()));
+//! check!(INTEGRATION_TEST_OUTPUT, regs.integration_test_output.read());
+//! check!(INTEGRATION_TEST_DATA, uart.read_integration_test_data_register());
+//! check!(INTEGRATION_TEST_DATA, uart.write_integration_test_data_register(Default::default()));
+//! check!(INTEGRATION_TEST_DATA, regs.integration_test_data.read());
+//! check!(PERIPHERAL_ID_0, uart.read_peripheral_id_0(), regs.peripheral_id_0.read());
+//! check!(PERIPHERAL_ID_1, uart.read_peripheral_id_1(), regs.peripheral_id_1.read());
+//! check!(PERIPHERAL_ID_2, uart.read_peripheral_id_2(), regs.peripheral_id_2.read());
+//! check!(PERIPHERAL_ID_3, uart.read_peripheral_id_3(), regs.peripheral_id_3.read());
+//! check!(PRIMECELL_ID_0, uart.read_primecell_id_0(), regs.primecell_id_0.read());
+//! check!(PRIMECELL_ID_1, uart.read_primecell_id_1(), regs.primecell_id_1.read());
+//! check!(PRIMECELL_ID_2, uart.read_primecell_id_2(), regs.primecell_id_2.read());
+//! check!(PRIMECELL_ID_3, uart.read_primecell_id_3(), regs.primecell_id_3.read());
+//! check!(FLAG, uart.read_raw_u32(FLAG), uart.write_raw_u32(FLAG, 0));
+//! check!(0xC0, uart.read_raw_u32(0xC0), uart.write_raw_u32(0xC0, 0));
+//! assert_eq!(uart.data_register_ptr() as usize, DATA);
+//! assert_eq!(uart.receive_status_register_ptr() as usize, RECEIVE_STATUS);
+//! assert_eq!(uart.error_clear_register_ptr() as usize, ERROR_CLEAR);
+//! assert_eq!(uart.flag_register_ptr() as usize, FLAG);
+//! assert_eq!(uart.irda_low_power_register_ptr() as usize, IRDA_LOW_POWER);
+//! assert_eq!(uart.integer_baud_rate_divisor_register_ptr() as usize, INTEGER_BAUD_RATE_DIVISOR);
+//! assert_eq!(uart.fractional_baud_rate_divisor_register_ptr() as usize, FRACTIONAL_BAUD_RATE_DIVISOR);
+//! assert_eq!(uart.line_control_register_ptr() as usize, LINE_CONTROL);
+//! assert_eq!(uart.control_register_ptr() as usize, CONTROL);
+//! assert_eq!(uart.interrupt_fifo_level_select_register_ptr() as usize, INTERRUPT_FIFO_LEVEL_SELECT);
+//! assert_eq!(uart.interrupt_mask_set_clear_register_ptr() as usize, INTERRUPT_MASK_SET_CLEAR);
+//! assert_eq!(uart.raw_interrupt_status_register_ptr() as usize, RAW_INTERRUPT_STATUS);
+//! assert_eq!(uart.masked_interrupt_status_register_ptr() as usize, MASKED_INTERRUPT_STATUS);
+//! assert_eq!(uart.interrupt_clear_register_ptr() as usize, INTERRUPT_CLEAR);
+//! assert_eq!(uart.dma_control_register_ptr() as usize, DMA_CONTROL);
+//! ```
+
+/// The size of the register window in bytes. Every register, including the identification registers at the top,
+/// is inside it.
+pub const REGISTER_WINDOW: usize = 0x1000;
+
+/// Data register, `UARTDR`.
+pub const DATA: usize = 0x00;
+/// Receive status register, `UARTRSR`, read-only.
+pub const RECEIVE_STATUS: usize = 0x04;
+/// Error clear register, `UARTECR`, write-only.
+pub const ERROR_CLEAR: usize = 0x04;
+/// Flag register, `UARTFR`.
+pub const FLAG: usize = 0x18;
+/// IrDA low-power counter register, `UARTILPR`.
+pub const IRDA_LOW_POWER: usize = 0x20;
+/// Integer baud rate divisor register, `UARTIBRD`.
+pub const INTEGER_BAUD_RATE_DIVISOR: usize = 0x24;
+/// Fractional baud rate divisor register, `UARTFBRD`.
+pub const FRACTIONAL_BAUD_RATE_DIVISOR: usize = 0x28;
+/// Line control register, `UARTLCR_H`.
+pub const LINE_CONTROL: usize = 0x2C;
+/// Control register, `UARTCR`.
+pub const CONTROL: usize = 0x30;
+/// Interrupt FIFO level select register, `UARTIFLS`.
+pub const INTERRUPT_FIFO_LEVEL_SELECT: usize = 0x34;
+/// Interrupt mask set/clear register, `UARTIMSC`.
+pub const INTERRUPT_MASK_SET_CLEAR: usize = 0x38;
+/// Raw interrupt status register, `UARTRIS`.
+pub const RAW_INTERRUPT_STATUS: usize = 0x3C;
+/// Masked interrupt status register, `UARTMIS`.
+pub const MASKED_INTERRUPT_STATUS: usize = 0x40;
+/// Interrupt clear register, `UARTICR`.
+pub const INTERRUPT_CLEAR: usize = 0x44;
+/// DMA control register, `UARTDMACR`.
+pub const DMA_CONTROL: usize = 0x48;
+/// Test control register, `UARTTCR`.
+pub const TEST_CONTROL: usize = 0x80;
+/// Integration test input register, `UARTITIP`.
+pub const INTEGRATION_TEST_INPUT: usize = 0x84;
+/// Integration test output register, `UARTITOP`.
+pub const INTEGRATION_TEST_OUTPUT: usize = 0x88;
+/// Test data register, `UARTTDR`.
+pub const INTEGRATION_TEST_DATA: usize = 0x8C;
+/// Peripheral identification register 0, `UARTPeriphID0`.
+pub const PERIPHERAL_ID_0: usize = 0xFE0;
+/// Peripheral identification register 1, `UARTPeriphID1`.
+pub const PERIPHERAL_ID_1: usize = 0xFE4;
+/// Peripheral identification register 2, `UARTPeriphID2`.
+pub const PERIPHERAL_ID_2: usize = 0xFE8;
+/// Peripheral identification register 3, `UARTPeriphID3`.
+pub const PERIPHERAL_ID_3: usize = 0xFEC;
+/// PrimeCell identification register 0, `UARTPCellID0`.
+pub const PRIMECELL_ID_0: usize = 0xFF0;
+/// PrimeCell identification register 1, `UARTPCellID1`.
+pub const PRIMECELL_ID_1: usize = 0xFF4;
+/// PrimeCell identification register 2, `UARTPCellID2`.
+pub const PRIMECELL_ID_2: usize = 0xFF8;
+/// PrimeCell identification register 3, `UARTPCellID3`.
+pub const PRIMECELL_ID_3: usize = 0xFFC;
@@ -1,5 +1,10 @@
 //! This module contains the definitions of the registers for the UART peripheral.
-use bitstuff::ints::u6;
+//!
+//! Registers compare and hash by their raw value, so two values that differ only in reserved bits are not equal.
+//!
+//! Every register type is `#[must_use]`. The `with_*` setters return a changed copy rather than changing the register
+//! in place, so `control.with_uart_enable(true);` on its own does nothing and is flagged by the compiler.
+use bitstuff::ints::{u2, u3, u4, u6};
 use core::num::{NonZeroU8, NonZeroU16};
 
 /// The UARTDR Register; the data register.
@@ -15,7 +20,8 @@ use core::num::{NonZeroU8, NonZeroU16};
 ///
 /// The received data byte is read by performing reads from the UARTDR Register along with the corresponding status information. The status information can also be read by a read of the UARTRSR/UARTECR Register.
 #[bitstuff::stuff(u32)]
-#[derive(Default)]
+#[derive(Default, PartialEq, Eq, Hash)]
+#[must_use]
 pub struct DataRegister {
     /// This bit is set to 1 if data is received and the receive FIFO is already full.
     /// This is cleared to 0 once there is an empty space in the FIFO and a new character can be written to it.
@@ -41,13 +47,16 @@ pub struct DataRegister {
     data: u8,
 }
 
-/// The UARTRSR/UARTECR Register; the receive status register/error clear register.
+/// The UARTRSR Register; the receive status register.
+///
+/// This is the read view of offset 0x04. Writing to the same offset accesses the error clear register, see [`ErrorClearRegister`].
 ///
 /// Receive status can also be read from the UARTRSR Register. If the status is read from this register, then the status information for break, framing and parity corresponds to the data character read from the Data Register, UARTDR prior to reading the UARTRSR Register. The status information for overrun is set immediately when an overrun condition occurs.
 ///
-/// A write to this register clears the framing, parity, break, and overrun errors. The data value is not important. All the bits are cleared to 0 on reset.
+/// All the bits are cleared to 0 on reset.
 #[bitstuff::stuff(u32)]
-#[derive(Default)]
+#[derive(Default, PartialEq, Eq, Hash)]
+#[must_use]
 pub struct ReceiveStatusRegister {
     /// This bit is set to 1 if data is received and the FIFO is already full.
     ///
@@ -79,12 +88,32 @@ pub struct ReceiveStatusRegister {
     framing_error: bool,
 }
 
+/// The UARTECR Register; the error clear register.
+///
+/// This is the write view of offset 0x04, which reads as the [`ReceiveStatusRegister`].
+/// A write to this register clears the framing, parity, break, and overrun errors. The data value is not important,
+/// so this type has no fields and only exists to give the write the width of the register.
+/// Keeping it separate from [`ReceiveStatusRegister`] means a status value that was read can't be written back by accident.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[must_use]
+pub struct ErrorClearRegister(u32);
+
+impl ErrorClearRegister {
+    pub const fn new() -> Self {
+        Self(0)
+    }
+}
+
 //note: read only, could do without the "with" functions but they can be useful for testing i suppose
 /// The UARTFR Register; the flag register.
 ///
 /// After reset TXFF, RXFF, and BUSY are 0, and TXFE and RXFE are 1.
 #[bitstuff::stuff(u32)]
-#[derive(Default)]
+#[derive(Default, PartialEq, Eq, Hash)]
+#[must_use]
 pub struct FlagRegister {
     /// This bit is the complement of the UART ring indicator, nUARTRI, modem status input. That is, the bit is 1 when nUARTRI is LOW.
     #[bitstuff(bit = 8)]
@@ -135,6 +164,24 @@ pub struct FlagRegister {
     clear_to_send: bool,
 }
 
+impl FlagRegister {
+    /// Returns `true` if there is room for at least one more character in the transmit FIFO (TXFF is clear).
+    pub fn can_transmit(&self) -> bool {
+        !self.transmit_fifo_full()
+    }
+
+    /// Returns `true` if there is at least one received character to read (RXFE is clear).
+    pub fn can_receive(&self) -> bool {
+        !self.receive_fifo_empty()
+    }
+
+    /// Returns `true` if everything written has been sent: the transmit FIFO is empty and BUSY is clear,
+    /// so the stop bits of the last character have left the UART as well.
+    pub fn is_idle(&self) -> bool {
+        self.transmit_fifo_empty() && !self.uart_busy()
+    }
+}
+
 //note: since this is a wrapper around a single field. Maybe we should extend the macro to support Struct(u8) or something like that
 /// The UARTILPR Register; the IrDA low-power counter register.
 ///
@@ -150,21 +197,57 @@ pub struct FlagRegister {
 /// You must select the divisor so that 1.42MHz < FIrLPBaud16 < 2.12MHz, results in a low-power pulse duration of 1.41 - 2.11µs (three times the period of IrLPBaud16).
 ///
 /// Note: In low-power IrDA mode the UART rejects random noise on the received serial data input by ignoring SIRIN pulses that are less than 3 periods of IrLPBaud16.
+///
+/// [`IrDALowPowerRegister::for_clock`] picks a valid divisor for a UARTCLK frequency.
+///
+/// The register resets to 0, which is not a valid divisor. [`IrDALowPowerRegister::default`] instead returns the smallest valid divisor, 1.
 #[bitstuff::stuff(u8)]
-// todo: fix default being zero here
-#[derive(Default)]
+#[derive(PartialEq, Eq, Hash)]
+#[must_use]
 pub struct IrDALowPowerRegister {
     /// These bits are cleared to 0 at reset.
     #[bitstuff(bits = 0..=7, falliable)]
     low_power_divisor_value: NonZeroU8,
 }
 
+/// Returns a divisor of 1 rather than the reset value of 0, so the default decodes:
+///
+/// ```
+/// use pl011_uart_registers::registrers::IrDALowPowerRegister;
+///
+/// let register = IrDALowPowerRegister::default();
+/// assert_eq!(register.low_power_divisor_value().map(|v| v.get()), Ok(1));
+/// assert_eq!(IrDALowPowerRegister::RESET.low_power_divisor_value(), Err(0));
+/// ```
+impl Default for IrDALowPowerRegister {
+    fn default() -> Self {
+        Self::new(NonZeroU8::MIN)
+    }
+}
+
+impl IrDALowPowerRegister {
+    /// Builds the register from a divisor, which can't be zero.
+    pub const fn new(low_power_divisor_value: NonZeroU8) -> Self {
+        Self(low_power_divisor_value.get())
+    }
+
+    /// Returns the register if its divisor is valid (non-zero), or the raw value otherwise.
+    pub fn validate(self) -> Result<Self, u8> {
+        match self.low_power_divisor_value() {
+            Ok(_) => Ok(self),
+            Err(_) => Err(self.0),
+        }
+    }
+}
+
 /// The UARTIBRD Register; the integer baud rate divisor register.
 ///
 /// The baud rate divisor is calculated as follows:
 ///
-///     Baud rate divisor BAUDDIV = (FUARTCLK / (16 x Baud rate))
-///     where FUARTCLK is the UART reference clock frequency.
+/// ```text
+/// Baud rate divisor BAUDDIV = (FUARTCLK / (16 x Baud rate))
+/// where FUARTCLK is the UART reference clock frequency.
+/// ```
 ///
 /// The BAUDDIV is comprised of the integer value (BAUD DIVINT) and the fractional value (BAUD DIVFRAC).
 ///
@@ -173,20 +256,55 @@ pub struct IrDALowPowerRegister {
 /// The minimum divide ratio possible is 1 and the maximum is 65535(216 - 1). That is, UARTIBRD = 0 is invalid and UARTFBRD is ignored when this is the case.
 ///
 /// Similarly, when UARTIBRD = 65535 (that is 0xFFFF), then UARTFBRD must not be greater than zero. If this is exceeded it results in an aborted transmission or reception.
+///
+/// The register resets to 0, which is invalid. [`IntegerBaudRateDivisorRegister::default`] instead returns the smallest valid divisor, 1.
 #[bitstuff::stuff(u16)]
-#[derive(Default)]
+#[derive(PartialEq, Eq, Hash)]
+#[must_use]
 pub struct IntegerBaudRateDivisorRegister {
     /// These bits are cleared to 0 at reset.
     #[bitstuff(bits = 0..=15, falliable)]
     integer_baud_rate_divisor: NonZeroU16,
 }
 
+/// Returns a divisor of 1 rather than the reset value of 0, so the default decodes:
+///
+/// ```
+/// use pl011_uart_registers::registrers::IntegerBaudRateDivisorRegister;
+///
+/// let register = IntegerBaudRateDivisorRegister::default();
+/// assert_eq!(register.integer_baud_rate_divisor().map(|v| v.get()), Ok(1));
+/// assert_eq!(IntegerBaudRateDivisorRegister::RESET.integer_baud_rate_divisor(), Err(0));
+/// ```
+impl Default for IntegerBaudRateDivisorRegister {
+    fn default() -> Self {
+        Self::new(NonZeroU16::MIN)
+    }
+}
+
+impl IntegerBaudRateDivisorRegister {
+    /// Builds the register from a divisor, which can't be zero.
+    pub const fn new(integer_baud_rate_divisor: NonZeroU16) -> Self {
+        Self(integer_baud_rate_divisor.get())
+    }
+
+    /// Returns the register if its divisor is valid (non-zero), or the raw value otherwise.
+    pub fn validate(self) -> Result<Self, u16> {
+        match self.integer_baud_rate_divisor() {
+            Ok(_) => Ok(self),
+            Err(_) => Err(self.0),
+        }
+    }
+}
+
 /// The UARTFBRD Register; the fractional part of the baud rate divisor value.
 ///
 /// The baud rate divisor is calculated as follows:
 ///
-///     Baud rate divisor BAUDDIV = (FUARTCLK / (16 x Baud rate))
-///     where FUARTCLK is the UART reference clock frequency.
+/// ```text
+/// Baud rate divisor BAUDDIV = (FUARTCLK / (16 x Baud rate))
+/// where FUARTCLK is the UART reference clock frequency.
+/// ```
 ///
 /// The BAUDDIV is comprised of the integer value (BAUD DIVINT) and the fractional value (BAUD DIVFRAC).
 ///
@@ -197,7 +315,8 @@ pub struct IntegerBaudRateDivisorRegister {
 /// Similarly, when UARTIBRD = 65535 (that is 0xFFFF), then UARTFBRD must not be greater than zero. If this is exceeded it results in an aborted transmission or reception.
 
 #[bitstuff::stuff(u8)]
-#[derive(Default)]
+#[derive(Default, PartialEq, Eq, Hash)]
+#[must_use]
 pub struct FractionalBaudRateDivisorRegister {
     /// These bits are cleared to 0 at reset.
     #[bitstuff(bits = 0..=5)]
@@ -205,7 +324,9 @@ pub struct FractionalBaudRateDivisorRegister {
 }
 
 /// the number of data bits transmitted or received in a frame
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[bitstuff::stuff]
 pub enum WordLength {
     #[default]
@@ -215,12 +336,161 @@ pub enum WordLength {
     EightBits = 0b11,
 }
 
+impl WordLength {
+    /// Every word length, from five to eight bits.
+    pub const fn all() -> &'static [WordLength] {
+        &[
+            WordLength::FiveBits,
+            WordLength::SixBits,
+            WordLength::SevenBits,
+            WordLength::EightBits,
+        ]
+    }
+
+    /// The word length for `bits` data bits, or `None` if it isn't between 5 and 8.
+    ///
+    /// ```
+    /// use pl011_uart_registers::registrers::WordLength;
+    ///
+    /// assert_eq!(WordLength::from_data_bits(5), Some(WordLength::FiveBits));
+    /// assert_eq!(WordLength::from_data_bits(6), Some(WordLength::SixBits));
+    /// assert_eq!(WordLength::from_data_bits(7), Some(WordLength::SevenBits));
+    /// assert_eq!(WordLength::from_data_bits(8), Some(WordLength::EightBits));
+    /// assert_eq!(WordLength::from_data_bits(4), None);
+    /// assert_eq!(WordLength::from_data_bits(9), None);
+    /// for &word_length in WordLength::all() {
+    ///     assert_eq!(WordLength::from_data_bits(word_length.data_bits()), Some(word_length));
+    /// }
+    /// ```
+    pub const fn from_data_bits(bits: u8) -> Option<WordLength> {
+        match bits {
+            5 => Some(WordLength::FiveBits),
+            6 => Some(WordLength::SixBits),
+            7 => Some(WordLength::SevenBits),
+            8 => Some(WordLength::EightBits),
+            _ => None,
+        }
+    }
+
+    /// The number of data bits, from 5 to 8.
+    pub const fn data_bits(self) -> u8 {
+        match self {
+            WordLength::FiveBits => 5,
+            WordLength::SixBits => 6,
+            WordLength::SevenBits => 7,
+            WordLength::EightBits => 8,
+        }
+    }
+}
+
+/// the number of stop bits transmitted at the end of a frame
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[bitstuff::stuff]
+pub enum StopBits {
+    #[default]
+    One = 0,
+    Two = 1,
+}
+
+/// Which parity the UART generates and checks, the EPS bit of the [`LineControlRegister`].
+///
+/// This only picks between odd and even: it has no effect while parity is disabled, and with stick parity
+/// it selects the level of the parity bit instead, see [`Parity`].
+///
+/// ```
+/// use pl011_uart_registers::registrers::{LineControlRegister, Parity, ParityPolarity};
+///
+/// let lcr = LineControlRegister::default()
+///     .with_parity_enable(true)
+///     .with_parity_polarity(ParityPolarity::Even);
+/// assert_eq!(lcr.bits(), 0b110);
+/// assert_eq!(lcr.parity_polarity(), ParityPolarity::Even);
+/// assert_eq!(lcr.parity(), Parity::Even);
+/// assert_eq!(lcr.with_parity_polarity(ParityPolarity::Odd).parity(), Parity::Odd);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[bitstuff::stuff]
+pub enum ParityPolarity {
+    /// An odd number of 1s in the data and parity bits.
+    #[default]
+    Odd = 0,
+    /// An even number of 1s in the data and parity bits.
+    Even = 1,
+}
+
+/// The parity mode, decoded from the PEN, EPS and SPS bits of the [`LineControlRegister`].
+///
+/// Every mode round-trips through the register, whatever the other bits are set to:
+///
+/// ```
+/// use pl011_uart_registers::registrers::{LineControlRegister, Parity, ParityPolarity};
+///
+/// for start in [LineControlRegister::from_bits(0), LineControlRegister::from_bits(0xFF)] {
+///     for &parity in Parity::all() {
+///         let lcr = start.with_parity(parity);
+///         assert_eq!(lcr.parity(), parity);
+///         assert_eq!(lcr.word_length(), start.word_length());
+///         assert_eq!(lcr.send_break(), start.send_break());
+///     }
+/// }
+/// let stick_low = LineControlRegister::from_bits(0).with_parity(Parity::StickLow);
+/// assert!(stick_low.parity_enable() && stick_low.stick_parity());
+/// assert_eq!(stick_low.parity_polarity(), ParityPolarity::Even);
+/// // with parity disabled the other two bits don't matter
+/// assert_eq!(LineControlRegister::from_bits(0b1000_0100).parity(), Parity::None);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Parity {
+    /// No parity bit is added to the frame.
+    #[default]
+    None,
+    /// The parity bit makes the number of 1s in the data and parity bits odd.
+    Odd,
+    /// The parity bit makes the number of 1s in the data and parity bits even.
+    Even,
+    /// The parity bit is always transmitted and checked as a 1.
+    StickHigh,
+    /// The parity bit is always transmitted and checked as a 0.
+    StickLow,
+}
+
+impl Parity {
+    /// The parity enable, stick parity and even parity select bits for this mode.
+    const fn control_bits(self) -> (bool, bool, bool) {
+        match self {
+            Parity::None => (false, false, false),
+            Parity::Odd => (true, false, false),
+            Parity::Even => (true, false, true),
+            Parity::StickHigh => (true, true, false),
+            Parity::StickLow => (true, true, true),
+        }
+    }
+
+    /// Every parity mode.
+    pub const fn all() -> &'static [Parity] {
+        &[
+            Parity::None,
+            Parity::Odd,
+            Parity::Even,
+            Parity::StickHigh,
+            Parity::StickLow,
+        ]
+    }
+}
+
 /// The UARTLCR_H Register; the line control register.
 ///
 /// This register accesses bits 29 to 22 of the UART Line Control Register, UARTLCR.
 /// All the bits are cleared to 0 when reset.
 #[bitstuff::stuff(u16)]
-#[derive(Default)]
+#[derive(Default, PartialEq, Eq, Hash)]
+#[must_use]
 pub struct LineControlRegister {
     /// `false` = stick parity is disabled
     ///
@@ -237,18 +507,16 @@ pub struct LineControlRegister {
     ///  - `true` = transmit and receive FIFO buffers are enabled (FIFO mode).
     #[bitstuff(bit = 4)]
     enable_fifos: bool,
-    //note: we could use an stop bits enum here, just for printing/clarity
-    /// Two stop bits select. If this bit is set to 1, two stop bits are transmitted at the end of the frame.
+    /// Two stop bits select, STP2. If this is [`StopBits::Two`], two stop bits are transmitted at the end of the frame.
     /// The receive logic does not check for two stop bits being received.
-    #[bitstuff(bit = 3)]
-    two_stop_bits_select: bool,
-    //note: we could use and EvenOdd enum here
-    /// Controls the type of parity the UART uses during transmission and reception:
-    ///  - `false` = odd parity. The UART generates or checks for an odd number of 1s in the data and parity bits.
-    ///  - `true` = even parity. The UART generates or checks for an even number of 1s in the data and parity bits.
+    #[bitstuff(bits = 3..=3)]
+    stop_bits: StopBits,
+    /// Even parity select, EPS. Controls the type of parity the UART uses during transmission and reception:
+    ///  - [`ParityPolarity::Odd`]: the UART generates or checks for an odd number of 1s in the data and parity bits.
+    ///  - [`ParityPolarity::Even`]: the UART generates or checks for an even number of 1s in the data and parity bits.
     /// This bit has no effect when the PEN bit disables parity checking and generation.
-    #[bitstuff(bit = 2)]
-    even_parity_select: bool,
+    #[bitstuff(bits = 2..=2)]
+    parity_polarity: ParityPolarity,
     /// Parity enable:
     /// - `false` = parity is disabled and no parity bit added to the data frame
     /// - `true` = parity checking and generation is enabled.
@@ -264,11 +532,199 @@ pub struct LineControlRegister {
     send_break: bool,
 }
 
+impl LineControlRegister {
+    /// Decodes the parity mode from the parity enable, even parity select and stick parity bits.
+    ///
+    /// When parity is disabled the other two bits are ignored.
+    pub fn parity(&self) -> Parity {
+        match (
+            self.parity_enable(),
+            self.stick_parity(),
+            self.parity_polarity(),
+        ) {
+            (false, _, _) => Parity::None,
+            (true, false, ParityPolarity::Odd) => Parity::Odd,
+            (true, false, ParityPolarity::Even) => Parity::Even,
+            (true, true, ParityPolarity::Odd) => Parity::StickHigh,
+            (true, true, ParityPolarity::Even) => Parity::StickLow,
+        }
+    }
+
+    /// Sets the parity enable, even parity select and stick parity bits together for `parity`.
+    ///
+    /// [`Parity::None`] clears all three.
+    pub fn with_parity(self, parity: Parity) -> Self {
+        let (enable, stick, even) = parity.control_bits();
+        self.with_parity_enable(enable)
+            .with_stick_parity(stick)
+            .with_parity_polarity(if even {
+                ParityPolarity::Even
+            } else {
+                ParityPolarity::Odd
+            })
+    }
+
+    /// Returns `true` if even parity is selected.
+    #[deprecated(note = "use `parity_polarity` instead")]
+    pub fn even_parity_select(&self) -> bool {
+        self.parity_polarity() == ParityPolarity::Even
+    }
+
+    /// Selects even parity if `value` is `true`, odd parity otherwise.
+    #[deprecated(note = "use `with_parity_polarity` instead")]
+    pub fn with_even_parity_select(self, value: bool) -> Self {
+        self.with_parity_polarity(if value {
+            ParityPolarity::Even
+        } else {
+            ParityPolarity::Odd
+        })
+    }
+
+    /// Returns `true` if two stop bits are selected.
+    #[deprecated(note = "use `stop_bits` instead")]
+    pub fn two_stop_bits_select(&self) -> bool {
+        self.stop_bits() == StopBits::Two
+    }
+
+    /// Selects two stop bits if `value` is `true`, one otherwise.
+    #[deprecated(note = "use `with_stop_bits` instead")]
+    pub fn with_two_stop_bits_select(self, value: bool) -> Self {
+        self.with_stop_bits(if value { StopBits::Two } else { StopBits::One })
+    }
+
+    /// Starts building a line control register from 8N1 with the FIFOs enabled, see [`LineControlBuilder`].
+    pub const fn builder() -> LineControlBuilder {
+        LineControlBuilder::new()
+    }
+}
+
+/// Builds a [`LineControlRegister`] from the serial format, instead of from the individual bits.
+///
+/// Parity is set as a single [`Parity`] so the PEN, EPS and SPS bits can't disagree with each other,
+/// and the FIFOs are enabled unless turned off explicitly. Break is never set.
+///
+/// ```
+/// use pl011_uart_registers::registrers::{LineControlRegister, Parity, StopBits, WordLength};
+///
+/// let lcr = LineControlRegister::builder()
+///     .word_length(WordLength::EightBits)
+///     .parity(Parity::None)
+///     .stop_bits(StopBits::One)
+///     .fifos(true)
+///     .build();
+/// assert_eq!(lcr.bits(), 0x70);
+/// assert_eq!(lcr.to_mode_string().as_str(), "8N1");
+///
+/// // `build` is a `const fn`, so this can also be a constant
+/// const LINE_CONTROL: LineControlRegister = LineControlRegister::builder()
+///     .word_length(WordLength::SevenBits)
+///     .parity(Parity::Even)
+///     .stop_bits(StopBits::Two)
+///     .build();
+/// assert_eq!(LINE_CONTROL.to_mode_string().as_str(), "7E2");
+/// assert!(LINE_CONTROL.enable_fifos());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[must_use = "call `build` to get the register value"]
+pub struct LineControlBuilder {
+    word_length: WordLength,
+    parity: Parity,
+    stop_bits: StopBits,
+    enable_fifos: bool,
+}
+
+impl Default for LineControlBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LineControlBuilder {
+    /// 8 data bits, no parity, one stop bit, FIFOs enabled.
+    pub const fn new() -> Self {
+        LineControlBuilder {
+            word_length: WordLength::EightBits,
+            parity: Parity::None,
+            stop_bits: StopBits::One,
+            enable_fifos: true,
+        }
+    }
+
+    pub const fn word_length(mut self, word_length: WordLength) -> Self {
+        self.word_length = word_length;
+        self
+    }
+
+    /// Sets the number of data bits from 5 to 8, or returns `None` for any other number.
+    pub const fn data_bits(self, data_bits: u8) -> Option<Self> {
+        match WordLength::from_data_bits(data_bits) {
+            Some(word_length) => Some(self.word_length(word_length)),
+            None => None,
+        }
+    }
+
+    pub const fn parity(mut self, parity: Parity) -> Self {
+        self.parity = parity;
+        self
+    }
+
+    pub const fn stop_bits(mut self, stop_bits: StopBits) -> Self {
+        self.stop_bits = stop_bits;
+        self
+    }
+
+    /// Enables or disables the transmit and receive FIFOs. With them disabled, the UART works in character mode.
+    pub const fn fifos(mut self, enable_fifos: bool) -> Self {
+        self.enable_fifos = enable_fifos;
+        self
+    }
+
+    /// The line control register value. This is a `const fn`, so it can be used for a `const` register value.
+    pub const fn build(self) -> LineControlRegister {
+        let (enable, stick, even) = self.parity.control_bits();
+        LineControlRegister::from_bits(
+            (stick as u16) << 7
+                | (self.word_length as u16) << 5
+                | (self.enable_fifos as u16) << 4
+                | (self.stop_bits as u16) << 3
+                | (even as u16) << 2
+                | (enable as u16) << 1,
+        )
+    }
+}
+
+impl LineControlRegister {
+    /// The raw encoding of the `word_length` field (bits 5 and 6), without decoding it to a [`WordLength`].
+    pub fn word_length_bits(&self) -> u2 {
+        u2::new(((self.0 >> 5) & 0b11) as u8)
+    }
+}
+
 /// The UARTCR Register; the control register.
 ///
 /// All the bits are cleared to 0 on reset except for bits 9 and 8 that are set to 1.
+/// [`ControlRegister::RESET`] and [`ControlRegister::default`] are this reset value, and are the starting point for
+/// building a control register value: starting from `from_bits(0)` instead silently clears the transmit and receive
+/// enables.
+///
+/// The `with_*` setters are generated by `bitstuff` and can't be called in `const` contexts,
+/// but [`from_bits`](Self::from_bits), [`bits`](Self::bits) and [`RESET`](Self::RESET) can,
+/// so a fixed configuration can still be computed at compile time:
+///
+/// ```
+/// use pl011_uart_registers::registrers::ControlRegister;
+///
+/// // the reset value (TXE and RXE) with UARTEN, RTSEn and CTSEn set on top
+/// const CONTROL: ControlRegister =
+///     ControlRegister::from_bits(ControlRegister::RESET.bits() | 1 << 0 | 1 << 14 | 1 << 15);
+///
+/// assert!(CONTROL.uart_enable() && CONTROL.transmit_enable() && CONTROL.receive_enable());
+/// assert!(CONTROL.rts_hardware_flow_control_enable() && CONTROL.cts_hardware_flow_control_enable());
+/// assert!(!CONTROL.loopback_enable());
+/// ```
 #[bitstuff::stuff(u16)]
-#[derive(Default)]
+#[derive(PartialEq, Eq, Hash)]
+#[must_use]
 pub struct ControlRegister {
     /// If this bit is set to `true`, CTS hardware flow control is enabled. Data is only transmitted when the nUARTCTS signal is asserted.
     #[bitstuff(bit = 15)]
@@ -321,8 +777,45 @@ pub struct ControlRegister {
     uart_enable: bool,
 }
 
+/// Returns the reset value, `0x0300`, with `transmit_enable` and `receive_enable` set.
+///
+/// Earlier versions derived `Default` and returned all zeros, which has the transmit and receive sections disabled.
+/// Code that relied on that should use `ControlRegister::from_bits(0)` instead.
+///
+/// ```
+/// use pl011_uart_registers::registrers::ControlRegister;
+///
+/// let control = ControlRegister::default();
+/// assert_eq!(control.bits(), 0x300);
+/// assert_eq!(control, ControlRegister::RESET);
+/// assert!(control.transmit_enable() && control.receive_enable());
+/// assert!(!control.uart_enable());
+/// ```
+impl Default for ControlRegister {
+    fn default() -> Self {
+        Self::RESET
+    }
+}
+
 /// Receive and transmit interrupt FIFO level select trigger points.
-#[derive(Debug, Default)]
+///
+/// Each level is a 3-bit field, and the three encodings `0b101`, `0b110` and `0b111` are reserved. The decoded getters of
+/// [`InterruptFIFOLevelSelectRegister`] return those as an error; use the `*_or_default` getters to read them as
+/// [`FIFOLevelSelect::OneHalf`] instead.
+///
+/// The levels are ordered by the fill fraction they trigger at:
+///
+/// ```
+/// use pl011_uart_registers::registrers::FIFOLevelSelect;
+///
+/// assert!(FIFOLevelSelect::OneEighth < FIFOLevelSelect::OneHalf);
+/// assert!(FIFOLevelSelect::OneHalf < FIFOLevelSelect::SevenEighth);
+/// assert!(FIFOLevelSelect::all().is_sorted());
+/// assert_eq!(FIFOLevelSelect::ThreeFourth.fraction(), (3, 4));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[bitstuff::stuff]
 pub enum FIFOLevelSelect {
     /// Receive FIFO becomes ≥ 1/8 full
@@ -348,6 +841,33 @@ pub enum FIFOLevelSelect {
     SevenEighth = 0b100,
 }
 
+impl FIFOLevelSelect {
+    /// Every valid trigger level, from the lowest to the highest fill level.
+    ///
+    /// The encodings 0b101 to 0b111 are reserved and not included.
+    pub const fn all() -> &'static [FIFOLevelSelect] {
+        &[
+            FIFOLevelSelect::OneEighth,
+            FIFOLevelSelect::OneFourth,
+            FIFOLevelSelect::OneHalf,
+            FIFOLevelSelect::ThreeFourth,
+            FIFOLevelSelect::SevenEighth,
+        ]
+    }
+
+    /// The fill fraction the level triggers at, as `(numerator, denominator)`, e.g. `(1, 8)` for
+    /// [`FIFOLevelSelect::OneEighth`].
+    pub const fn fraction(self) -> (u8, u8) {
+        match self {
+            FIFOLevelSelect::OneEighth => (1, 8),
+            FIFOLevelSelect::OneFourth => (1, 4),
+            FIFOLevelSelect::OneHalf => (1, 2),
+            FIFOLevelSelect::ThreeFourth => (3, 4),
+            FIFOLevelSelect::SevenEighth => (7, 8),
+        }
+    }
+}
+
 /// The UARTIFLS Register; the interrupt FIFO level select register.
 ///
 /// You can use this register to define the FIFO level that triggers the assertion of UARTTXINTR and UARTRXINTR.
@@ -355,7 +875,10 @@ pub enum FIFOLevelSelect {
 /// The interrupts are generated based on a transition through a level rather than being based on the level. That is, the interrupts are generated when the fill level progresses through the trigger level.
 ///
 /// The bits are reset so that the trigger level is when the FIFOs are at the half-way mark.
+/// [`InterruptFIFOLevelSelectRegister::default`] returns this reset value.
 #[bitstuff::stuff(u16)]
+#[derive(PartialEq, Eq, Hash)]
+#[must_use]
 pub struct InterruptFIFOLevelSelectRegister {
     /// Receive FIFO interrupt level select.
     #[bitstuff(bits = 3..=5, falliable)]
@@ -367,6 +890,95 @@ pub struct InterruptFIFOLevelSelectRegister {
     transmit_interrupt_fifo_level_select: FIFOLevelSelect,
 }
 
+/// Returns the reset value, `0b010_010`, with both trigger levels at [`FIFOLevelSelect::OneHalf`]:
+///
+/// ```
+/// use pl011_uart_registers::registrers::{FIFOLevelSelect, InterruptFIFOLevelSelectRegister};
+///
+/// let fifo_level = InterruptFIFOLevelSelectRegister::default();
+/// assert_eq!(fifo_level.bits(), 0b010_010);
+/// assert_eq!(fifo_level.receive_interrupt_fifo_level_select(), Ok(FIFOLevelSelect::OneHalf));
+/// assert_eq!(fifo_level.transmit_interrupt_fifo_level_select(), Ok(FIFOLevelSelect::OneHalf));
+/// ```
+impl Default for InterruptFIFOLevelSelectRegister {
+    fn default() -> Self {
+        Self::RESET
+    }
+}
+
+impl InterruptFIFOLevelSelectRegister {
+    /// Builds the register from both trigger levels at once.
+    ///
+    /// ```
+    /// use pl011_uart_registers::registrers::{FIFOLevelSelect, InterruptFIFOLevelSelectRegister};
+    ///
+    /// for &rx in FIFOLevelSelect::all() {
+    ///     for &tx in FIFOLevelSelect::all() {
+    ///         let ifls = InterruptFIFOLevelSelectRegister::new(rx, tx);
+    ///         assert_eq!(ifls.receive_interrupt_fifo_level_select(), Ok(rx));
+    ///         assert_eq!(ifls.transmit_interrupt_fifo_level_select(), Ok(tx));
+    ///     }
+    /// }
+    /// let reset = InterruptFIFOLevelSelectRegister::new(FIFOLevelSelect::OneHalf, FIFOLevelSelect::OneHalf);
+    /// assert_eq!(reset, InterruptFIFOLevelSelectRegister::default());
+    /// ```
+    pub const fn new(rx: FIFOLevelSelect, tx: FIFOLevelSelect) -> Self {
+        Self::from_bits(((rx as u16) << 3) | tx as u16)
+    }
+
+    /// The receive trigger level, reading a reserved encoding as the reset value [`FIFOLevelSelect::OneHalf`].
+    ///
+    /// The UART never produces a reserved encoding by itself, so this only falls back if one was written to the
+    /// register. Use [`receive_interrupt_fifo_level_select`](Self::receive_interrupt_fifo_level_select) to tell the
+    /// two apart.
+    ///
+    /// ```
+    /// use pl011_uart_registers::registrers::{FIFOLevelSelect, InterruptFIFOLevelSelectRegister};
+    ///
+    /// let ifls = InterruptFIFOLevelSelectRegister::from_bits(0b111_000);
+    /// assert_eq!(ifls.receive_interrupt_fifo_level_select(), Err(0b111));
+    /// assert_eq!(ifls.receive_interrupt_fifo_level_or_default(), FIFOLevelSelect::OneHalf);
+    /// assert_eq!(ifls.transmit_interrupt_fifo_level_or_default(), FIFOLevelSelect::OneEighth);
+    /// ```
+    pub fn receive_interrupt_fifo_level_or_default(&self) -> FIFOLevelSelect {
+        self.receive_interrupt_fifo_level_select()
+            .unwrap_or_default()
+    }
+
+    /// The transmit trigger level, reading a reserved encoding as the reset value [`FIFOLevelSelect::OneHalf`].
+    ///
+    /// See [`receive_interrupt_fifo_level_or_default`](Self::receive_interrupt_fifo_level_or_default).
+    pub fn transmit_interrupt_fifo_level_or_default(&self) -> FIFOLevelSelect {
+        self.transmit_interrupt_fifo_level_select()
+            .unwrap_or_default()
+    }
+
+    /// The raw encoding of the receive trigger level (bits 3 to 5).
+    ///
+    /// Unlike the decoded getter this never fails, so reserved encodings can be preserved, e.g. when forwarding the value verbatim.
+    pub fn receive_interrupt_fifo_level_select_bits(&self) -> u3 {
+        u3::new(((self.0 >> 3) & 0b111) as u8)
+    }
+
+    /// The raw encoding of the transmit trigger level (bits 0 to 2).
+    ///
+    /// Unlike the decoded getter this never fails, so reserved encodings can be preserved, e.g. when forwarding the value verbatim.
+    pub fn transmit_interrupt_fifo_level_select_bits(&self) -> u3 {
+        u3::new((self.0 & 0b111) as u8)
+    }
+
+    /// Returns the register if both trigger levels use a defined encoding, or the raw value otherwise.
+    pub fn validate(self) -> Result<Self, u16> {
+        if self.receive_interrupt_fifo_level_select().is_ok()
+            && self.transmit_interrupt_fifo_level_select().is_ok()
+        {
+            Ok(self)
+        } else {
+            Err(self.0)
+        }
+    }
+}
+
 /// The UARTIMSC Register; the interrupt mask set/clear register.
 ///
 /// This register controls which interrupt sources are enabled (unmasked).
@@ -374,6 +986,8 @@ pub struct InterruptFIFOLevelSelectRegister {
 /// Writing true to a bit sets the corresponding mask (enables the interrupt), writing false clears it (disables the interrupt).
 /// All bits are cleared to false on reset.
 #[bitstuff::stuff(u16)]
+#[derive(PartialEq, Eq, Hash)]
+#[must_use]
 pub struct InterruptMaskSetClearRegister {
     /// Overrun error interrupt mask. When true, the overrun error interrupt is enabled.
     #[bitstuff(bit = 10)]
@@ -418,42 +1032,48 @@ pub struct InterruptMaskSetClearRegister {
 ///
 /// This register shows the status of the UART interrupt sources prior to masking.
 /// Each bit corresponds to a different interrupt source. A bit is set to true if the corresponding interrupt is asserted.
+/// The bits line up one-to-one with [`InterruptMaskSetClearRegister`].
+///
+/// This register is read-only, the `with_*` builders are only useful for testing.
+/// All bits are cleared to false on reset, except for the modem status bits which depend on the modem inputs.
 #[bitstuff::stuff(u16)]
+#[derive(Default, PartialEq, Eq, Hash)]
+#[must_use]
 pub struct RawInterruptStatusRegister {
-    /// Overrun error interrupt status (raw).
+    /// Overrun error raw interrupt status. When true, the overrun error interrupt is asserted, whether or not it is enabled in UARTIMSC.
     #[bitstuff(bit = 10)]
     overrun_error_raw_interrupt: bool,
-    /// Break error interrupt status (raw).
+    /// Break error raw interrupt status. When true, the break error interrupt is asserted, whether or not it is enabled in UARTIMSC.
     #[bitstuff(bit = 9)]
     break_error_raw_interrupt: bool,
-    /// Parity error interrupt status (raw).
+    /// Parity error raw interrupt status. When true, the parity error interrupt is asserted, whether or not it is enabled in UARTIMSC.
     #[bitstuff(bit = 8)]
     parity_error_raw_interrupt: bool,
-    /// Framing error interrupt status (raw).
+    /// Framing error raw interrupt status. When true, the framing error interrupt is asserted, whether or not it is enabled in UARTIMSC.
     #[bitstuff(bit = 7)]
     framing_error_raw_interrupt: bool,
-    /// Receive timeout interrupt status (raw).
+    /// Receive timeout raw interrupt status. When true, the receive timeout interrupt is asserted, whether or not it is enabled in UARTIMSC.
     #[bitstuff(bit = 6)]
     receive_timeout_raw_interrupt: bool,
-    /// Transmit interrupt status (raw).
+    /// Transmit raw interrupt status. When true, the transmit interrupt is asserted, whether or not it is enabled in UARTIMSC.
     #[bitstuff(bit = 5)]
     transmit_raw_interrupt: bool,
-    /// Receive interrupt status (raw).
+    /// Receive raw interrupt status. When true, the receive interrupt is asserted, whether or not it is enabled in UARTIMSC.
     #[bitstuff(bit = 4)]
     receive_raw_interrupt: bool,
-    /// nUARTDSR modem interrupt status (raw).
+    /// nUARTDSR modem raw interrupt status. When true, the nUARTDSR modem interrupt is asserted, whether or not it is enabled in UARTIMSC.
     #[bitstuff(bit = 3)]
     #[allow(non_snake_case)]
     n_uartdsr_modem_raw_interrupt: bool,
-    /// nUARTDCD modem interrupt status (raw).
+    /// nUARTDCD modem raw interrupt status. When true, the nUARTDCD modem interrupt is asserted, whether or not it is enabled in UARTIMSC.
     #[bitstuff(bit = 2)]
     #[allow(non_snake_case)]
     n_uartdcd_modem_raw_interrupt: bool,
-    /// nUARTCTS modem interrupt status (raw).
+    /// nUARTCTS modem raw interrupt status. When true, the nUARTCTS modem interrupt is asserted, whether or not it is enabled in UARTIMSC.
     #[bitstuff(bit = 1)]
     #[allow(non_snake_case)]
     n_uartcts_modem_raw_interrupt: bool,
-    /// nUARTRI modem interrupt status (raw).
+    /// nUARTRI modem raw interrupt status. When true, the nUARTRI modem interrupt is asserted, whether or not it is enabled in UARTIMSC.
     #[bitstuff(bit = 0)]
     #[allow(non_snake_case)]
     n_uartri_modem_raw_interrupt: bool,
@@ -463,52 +1083,75 @@ pub struct RawInterruptStatusRegister {
 ///
 /// This register shows the status of the UART interrupt sources after masking by the interrupt mask register.
 /// Each bit is set to true if the corresponding interrupt is asserted and not masked.
+///
+/// This is not a separate piece of state: it always reads as the raw interrupt status ANDed with the interrupt mask,
+/// see [`MaskedInterruptStatusRegister::from_raw_and_mask`]. Interrupt handlers want this register,
+/// [`RawInterruptStatusRegister`] also shows interrupts that are disabled.
+///
+/// This register is read-only, the `with_*` builders are only useful for testing.
 #[bitstuff::stuff(u16)]
+#[derive(Default, PartialEq, Eq, Hash)]
+#[must_use]
 pub struct MaskedInterruptStatusRegister {
     /// Overrun error interrupt status (masked).
     #[bitstuff(bit = 10)]
-    overrun_error_interrupt_mask: bool,
+    overrun_error_interrupt_status: bool,
     /// Break error interrupt status (masked).
     #[bitstuff(bit = 9)]
-    break_error_interrupt_mask: bool,
+    break_error_interrupt_status: bool,
     /// Parity error interrupt status (masked).
     #[bitstuff(bit = 8)]
-    parity_error_interrupt_mask: bool,
+    parity_error_interrupt_status: bool,
     /// Framing error interrupt status (masked).
     #[bitstuff(bit = 7)]
-    framing_error_interrupt_mask: bool,
+    framing_error_interrupt_status: bool,
     /// Receive timeout interrupt status (masked).
     #[bitstuff(bit = 6)]
-    receive_timeout_interrupt_mask: bool,
+    receive_timeout_interrupt_status: bool,
     /// Transmit interrupt status (masked).
     #[bitstuff(bit = 5)]
-    transmit_interrupt_mask: bool,
+    transmit_interrupt_status: bool,
     /// Receive interrupt status (masked).
     #[bitstuff(bit = 4)]
-    receive_interrupt_mask: bool,
+    receive_interrupt_status: bool,
     /// nUARTDSR modem interrupt status (masked).
     #[bitstuff(bit = 3)]
     #[allow(non_snake_case)]
-    n_uartdsr_modem_interrupt_mask: bool,
+    n_uartdsr_modem_interrupt_status: bool,
     /// nUARTDCD modem interrupt status (masked).
     #[bitstuff(bit = 2)]
     #[allow(non_snake_case)]
-    n_uartdcd_modem_interrupt_mask: bool,
+    n_uartdcd_modem_interrupt_status: bool,
     /// nUARTCTS modem interrupt status (masked).
     #[bitstuff(bit = 1)]
     #[allow(non_snake_case)]
-    n_uartcts_modem_interrupt_mask: bool,
+    n_uartcts_modem_interrupt_status: bool,
     /// nUARTRI modem interrupt status (masked).
     #[bitstuff(bit = 0)]
     #[allow(non_snake_case)]
-    n_uartri_modem_interrupt_mask: bool,
+    n_uartri_modem_interrupt_status: bool,
+}
+
+impl MaskedInterruptStatusRegister {
+    /// Computes the masked status the way the hardware does, `raw & mask`.
+    ///
+    /// Useful when the raw status and the mask have already been read, or to model the register in tests.
+    pub const fn from_raw_and_mask(
+        raw: RawInterruptStatusRegister,
+        mask: InterruptMaskSetClearRegister,
+    ) -> Self {
+        Self::from_bits(raw.bits() & mask.bits())
+    }
 }
 
 /// The UARTICR Register; the interrupt clear register.
 ///
 /// Writing true to a bit in this register clears the corresponding interrupt in the raw interrupt status register.
-/// Reading this register always returns zero.
+/// Writing false has no effect. This register is write-only.
+/// The bits line up one-to-one with [`InterruptMaskSetClearRegister`].
 #[bitstuff::stuff(u16)]
+#[derive(Default, PartialEq, Eq, Hash)]
+#[must_use]
 pub struct InterruptClearRegister {
     /// Clear overrun error interrupt.
     #[bitstuff(bit = 10)]
@@ -549,18 +1192,357 @@ pub struct InterruptClearRegister {
     clear_n_uartri_modem_interrupt: bool,
 }
 
+impl InterruptClearRegister {
+    /// A value with all eleven clear bits set, to acknowledge every interrupt at once.
+    pub const fn clear_all() -> Self {
+        Self(0x07FF)
+    }
+}
+
 /// The UARTDMACR Register; the DMA control register.
 ///
 /// This register controls the enabling of DMA for the UART transmitter and receiver, and whether DMA is enabled on error.
+/// All the bits are cleared to 0 on reset.
 #[bitstuff::stuff(u8)]
+#[derive(Default, PartialEq, Eq, Hash)]
+#[must_use]
 pub struct DMAControlRegister {
-    /// Enable DMA on error.
+    /// DMA on error, DMAONERR. If this bit is set to `true`, the DMA receive request outputs,
+    /// UARTRXDMASREQ or UARTRXDMABREQ, are disabled when the UART error interrupt is asserted.
     #[bitstuff(bit = 2)]
     dma_on_error: bool,
-    /// Enable DMA for transmitter.
+    /// Transmit DMA enable, TXDMAE. If this bit is set to `true`, DMA for the transmit FIFO is enabled.
     #[bitstuff(bit = 1)]
-    dma_transmit_enable: bool,
-    /// Enable DMA for receiver.
+    transmit_dma_enable: bool,
+    /// Receive DMA enable, RXDMAE. If this bit is set to `true`, DMA for the receive FIFO is enabled.
     #[bitstuff(bit = 0)]
-    dma_receive_enable: bool,
+    receive_dma_enable: bool,
+}
+
+/// The UARTTCR Register; the test control register.
+///
+/// This register selects the integration and FIFO test modes, and the SIR loopback path
+/// used by [`ControlRegister`]'s `loopback_enable` in SIR mode.
+/// It is only meant for integration testing and production test: the test modes change how the UART's
+/// inputs, outputs and FIFOs behave, so it should only be written while the UART is otherwise idle,
+/// and every bit must be cleared again before returning to normal operation.
+/// All the bits are cleared to 0 on reset.
+#[bitstuff::stuff(u8)]
+#[derive(Default, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct TestControlRegister {
+    /// SIR test enable, SIRTEST. When `true`, the nSIROUT path is looped back to SIRIN while `sir_enable` and
+    /// `loopback_enable` are set in the control register, overriding the normal half-duplex SIR operation.
+    /// This must be cleared when loopback testing is finished.
+    #[bitstuff(bit = 2)]
+    sir_test: bool,
+    /// Test FIFO enable, TESTFIFO. When `true`, reads from the integration test data register
+    /// read the transmit FIFO and writes write the receive FIFO, the reverse of normal operation.
+    #[bitstuff(bit = 1)]
+    test_fifo_enable: bool,
+    /// Integration test enable, ITEN. When `true`, the UART is in integration test mode:
+    /// its outputs are driven from the integration test output register and its inputs can be read from
+    /// the integration test input register instead of passing through the UART logic.
+    #[bitstuff(bit = 0)]
+    integration_test_enable: bool,
+}
+
+/// The UARTITIP Register; the integration test input register.
+///
+/// Reads return the current values of the UART's input signals. Only meaningful in integration test mode,
+/// see [`TestControlRegister`]'s `integration_test_enable`. The signals are reported as they are on the pins,
+/// so the active-low modem inputs read `false` when asserted.
+/// This register is read-only, the `with_*` builders are only useful for testing.
+#[bitstuff::stuff(u16)]
+#[derive(Default, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct IntegrationTestInputRegister {
+    /// The value of the nUARTCTS primary input.
+    #[bitstuff(bit = 7)]
+    #[allow(non_snake_case)]
+    n_uartcts: bool,
+    /// The value of the nUARTDCD primary input.
+    #[bitstuff(bit = 6)]
+    #[allow(non_snake_case)]
+    n_uartdcd: bool,
+    /// The value of the nUARTDSR primary input.
+    #[bitstuff(bit = 5)]
+    #[allow(non_snake_case)]
+    n_uartdsr: bool,
+    /// The value of the nUARTRI primary input.
+    #[bitstuff(bit = 4)]
+    #[allow(non_snake_case)]
+    n_uartri: bool,
+    /// The value of the UARTTXDMACLR intra-chip input.
+    #[bitstuff(bit = 3)]
+    uart_txdmaclr: bool,
+    /// The value of the UARTRXDMACLR intra-chip input.
+    #[bitstuff(bit = 2)]
+    uart_rxdmaclr: bool,
+    /// The value of the SIRIN primary input.
+    #[bitstuff(bit = 1)]
+    sirin: bool,
+    /// The value of the UARTRXD primary input.
+    #[bitstuff(bit = 0)]
+    uart_rxd: bool,
+}
+
+/// The UARTITOP Register; the integration test output register.
+///
+/// In integration test mode, see [`TestControlRegister`]'s `integration_test_enable`, writes drive the UART's
+/// output signals directly and reads return the values being driven. Outside of that mode writing this register
+/// has no effect on the outputs. As with [`IntegrationTestInputRegister`], the active-low outputs are set to
+/// their pin level, so writing `false` asserts them.
+#[bitstuff::stuff(u16)]
+#[derive(Default, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct IntegrationTestOutputRegister {
+    /// The value driven on the UARTMSINTR intra-chip output.
+    #[bitstuff(bit = 15)]
+    uart_msintr: bool,
+    /// The value driven on the UARTRXINTR intra-chip output.
+    #[bitstuff(bit = 14)]
+    uart_rxintr: bool,
+    /// The value driven on the UARTTXINTR intra-chip output.
+    #[bitstuff(bit = 13)]
+    uart_txintr: bool,
+    /// The value driven on the UARTRTINTR intra-chip output.
+    #[bitstuff(bit = 12)]
+    uart_rtintr: bool,
+    /// The value driven on the UARTEINTR intra-chip output.
+    #[bitstuff(bit = 11)]
+    uart_eintr: bool,
+    /// The value driven on the UARTINTR intra-chip output.
+    #[bitstuff(bit = 10)]
+    uart_intr: bool,
+    /// The value driven on the nUARTOut2 primary output.
+    #[bitstuff(bit = 9)]
+    #[allow(non_snake_case)]
+    n_uartout2: bool,
+    /// The value driven on the nUARTOut1 primary output.
+    #[bitstuff(bit = 8)]
+    #[allow(non_snake_case)]
+    n_uartout1: bool,
+    /// The value driven on the nUARTRTS primary output.
+    #[bitstuff(bit = 7)]
+    #[allow(non_snake_case)]
+    n_uartrts: bool,
+    /// The value driven on the nUARTDTR primary output.
+    #[bitstuff(bit = 6)]
+    #[allow(non_snake_case)]
+    n_uartdtr: bool,
+    /// The value driven on the UARTTXDMASREQ intra-chip output.
+    #[bitstuff(bit = 5)]
+    uart_txdmasreq: bool,
+    /// The value driven on the UARTTXDMABREQ intra-chip output.
+    #[bitstuff(bit = 4)]
+    uart_txdmabreq: bool,
+    /// The value driven on the UARTRXDMASREQ intra-chip output.
+    #[bitstuff(bit = 3)]
+    uart_rxdmasreq: bool,
+    /// The value driven on the UARTRXDMABREQ intra-chip output.
+    #[bitstuff(bit = 2)]
+    uart_rxdmabreq: bool,
+    /// The value driven on the nSIROUT primary output.
+    #[bitstuff(bit = 1)]
+    #[allow(non_snake_case)]
+    n_sirout: bool,
+    /// The value driven on the UARTTXD primary output.
+    #[bitstuff(bit = 0)]
+    uart_txd: bool,
+}
+
+/// The UARTTDR Register; the integration test data register.
+///
+/// With `test_fifo_enable` set in the [`TestControlRegister`], writes go into the receive FIFO and reads
+/// come from the transmit FIFO, which allows testing the FIFOs without going through the serial logic.
+/// The layout matches the [`DataRegister`]: a character and the four error flags stored alongside it.
+#[bitstuff::stuff(u16)]
+#[derive(Default, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct IntegrationTestDataRegister {
+    /// Overrun error flag stored with the character.
+    #[bitstuff(bit = 11)]
+    overrun_error: bool,
+    /// Break error flag stored with the character.
+    #[bitstuff(bit = 10)]
+    break_error: bool,
+    /// Parity error flag stored with the character.
+    #[bitstuff(bit = 9)]
+    parity_error: bool,
+    /// Framing error flag stored with the character.
+    #[bitstuff(bit = 8)]
+    framing_error: bool,
+    /// The character.
+    #[bitstuff(bits = 0..=7)]
+    data: u8,
+}
+
+/// The UARTPeriphID0 Register; bits 7 to 0 of the part number.
+///
+/// The four peripheral identification registers together read as a 32-bit value,
+/// see [`UART::read_peripheral_id`](crate::UART::read_peripheral_id).
+/// For the PL011 the part number is 0x011 and the designer is 0x41, ARM.
+#[bitstuff::stuff(u8)]
+#[derive(Default, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct PeripheralId0Register {
+    /// Bits 7 to 0 of the part number, 0x11 for the PL011.
+    #[bitstuff(bits = 0..=7)]
+    part_number_0: u8,
+}
+
+/// The UARTPeriphID1 Register; bits 11 to 8 of the part number and bits 3 to 0 of the designer.
+#[bitstuff::stuff(u8)]
+#[derive(Default, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct PeripheralId1Register {
+    /// Bits 3 to 0 of the designer identification, 0x1 for ARM.
+    #[bitstuff(bits = 4..=7)]
+    designer_0: u4,
+    /// Bits 11 to 8 of the part number, 0x0 for the PL011.
+    #[bitstuff(bits = 0..=3)]
+    part_number_1: u4,
+}
+
+/// The UARTPeriphID2 Register; the revision and bits 7 to 4 of the designer.
+#[bitstuff::stuff(u8)]
+#[derive(Default, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct PeripheralId2Register {
+    /// The revision number of the peripheral, this depends on the release of the PL011
+    /// (e.g. 0x3 for r1p5).
+    #[bitstuff(bits = 4..=7)]
+    revision: u4,
+    /// Bits 7 to 4 of the designer identification, 0x4 for ARM.
+    #[bitstuff(bits = 0..=3)]
+    designer_1: u4,
+}
+
+/// The UARTPeriphID3 Register; the configuration option of the peripheral.
+#[bitstuff::stuff(u8)]
+#[derive(Default, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct PeripheralId3Register {
+    /// The configuration option of the peripheral, 0x00 for the PL011.
+    #[bitstuff(bits = 0..=7)]
+    configuration: u8,
+}
+
+/// The UARTPCellID0 Register; bits 7 to 0 of the PrimeCell identification value, reads as 0x0D.
+///
+/// The four PrimeCell identification registers together read as 0xB105F00D on every PrimeCell peripheral,
+/// see [`UART::read_primecell_id`](crate::UART::read_primecell_id).
+#[bitstuff::stuff(u8)]
+#[derive(Default, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct PrimeCellId0Register {
+    /// Always 0x0D.
+    #[bitstuff(bits = 0..=7)]
+    primecell_id_0: u8,
+}
+
+/// The UARTPCellID1 Register; bits 15 to 8 of the PrimeCell identification value, reads as 0xF0.
+#[bitstuff::stuff(u8)]
+#[derive(Default, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct PrimeCellId1Register {
+    /// Always 0xF0.
+    #[bitstuff(bits = 0..=7)]
+    primecell_id_1: u8,
+}
+
+/// The UARTPCellID2 Register; bits 23 to 16 of the PrimeCell identification value, reads as 0x05.
+#[bitstuff::stuff(u8)]
+#[derive(Default, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct PrimeCellId2Register {
+    /// Always 0x05.
+    #[bitstuff(bits = 0..=7)]
+    primecell_id_2: u8,
+}
+
+/// The UARTPCellID3 Register; bits 31 to 24 of the PrimeCell identification value, reads as 0xB1.
+#[bitstuff::stuff(u8)]
+#[derive(Default, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct PrimeCellId3Register {
+    /// Always 0xB1.
+    #[bitstuff(bits = 0..=7)]
+    primecell_id_3: u8,
+}
+
+macro_rules! register_bits {
+    ($($register:ident: $bits:ty { used: $mask:expr, reset: $reset:expr },)*) => {
+        $(
+            impl $register {
+                /// The OR of the masks of every field of this register. Any bit outside of this mask is reserved.
+                pub const USED_BITS: $bits = $mask;
+
+                /// The value of this register after a hardware reset, as documented in the TRM.
+                ///
+                /// See [`UART::reset`](crate::UART::reset) to write these back to a UART.
+                pub const RESET: Self = Self($reset);
+
+                /// Builds the register from its raw value, as it would be read from the hardware.
+                ///
+                /// Like a hardware read this doesn't validate anything: reserved bits are kept, and fields that can
+                /// hold invalid values (e.g. a zero divisor) only report them when their getter is called.
+                pub const fn from_bits(bits: $bits) -> Self {
+                    Self(bits)
+                }
+
+                /// The raw value of the register, including any reserved bits.
+                ///
+                /// `Self::from_bits(register.bits())` always gives back an identical register.
+                pub const fn bits(self) -> $bits {
+                    self.0
+                }
+            }
+
+            /// Same as [`from_bits`](Self::from_bits), without any validation.
+            impl From<$bits> for $register {
+                fn from(bits: $bits) -> Self {
+                    Self::from_bits(bits)
+                }
+            }
+
+            impl From<$register> for $bits {
+                fn from(register: $register) -> Self {
+                    register.bits()
+                }
+            }
+        )*
+    };
+}
+
+// Reset values are from the TRM. The modem status bits of UARTFR follow the input pins and are taken as 0,
+// and the revision in UARTPeriphID2 is the one of the r1p5 release.
+register_bits! {
+    DataRegister: u32 { used: 0x0FFF, reset: 0 },
+    ReceiveStatusRegister: u32 { used: 0x000F, reset: 0 },
+    FlagRegister: u32 { used: 0x01FF, reset: 0x0090 },
+    IrDALowPowerRegister: u8 { used: 0xFF, reset: 0 },
+    IntegerBaudRateDivisorRegister: u16 { used: 0xFFFF, reset: 0 },
+    FractionalBaudRateDivisorRegister: u8 { used: 0x3F, reset: 0 },
+    LineControlRegister: u16 { used: 0x00FF, reset: 0 },
+    ControlRegister: u16 { used: 0xFF83, reset: 0x0300 },
+    InterruptFIFOLevelSelectRegister: u16 { used: 0x003F, reset: 0x0012 },
+    InterruptMaskSetClearRegister: u16 { used: 0x07FF, reset: 0 },
+    RawInterruptStatusRegister: u16 { used: 0x07FF, reset: 0 },
+    MaskedInterruptStatusRegister: u16 { used: 0x07FF, reset: 0 },
+    InterruptClearRegister: u16 { used: 0x07FF, reset: 0 },
+    DMAControlRegister: u8 { used: 0x07, reset: 0 },
+    TestControlRegister: u8 { used: 0x07, reset: 0 },
+    IntegrationTestInputRegister: u16 { used: 0x00FF, reset: 0 },
+    IntegrationTestOutputRegister: u16 { used: 0xFFFF, reset: 0 },
+    IntegrationTestDataRegister: u16 { used: 0x0FFF, reset: 0 },
+    PeripheralId0Register: u8 { used: 0xFF, reset: 0x11 },
+    PeripheralId1Register: u8 { used: 0xFF, reset: 0x10 },
+    PeripheralId2Register: u8 { used: 0xFF, reset: 0x34 },
+    PeripheralId3Register: u8 { used: 0xFF, reset: 0x00 },
+    PrimeCellId0Register: u8 { used: 0xFF, reset: 0x0D },
+    PrimeCellId1Register: u8 { used: 0xFF, reset: 0xF0 },
+    PrimeCellId2Register: u8 { used: 0xFF, reset: 0x05 },
+    PrimeCellId3Register: u8 { used: 0xFF, reset: 0xB1 },
 }
@@ -0,0 +1,63 @@
+//! Temporary changes to the UART configuration that are undone afterwards.
+use crate::{BaseAddress, UART, registrers::ControlRegister};
+
+/// Writes a saved control register back when dropped, including during unwinding.
+///
+/// Returned by [`UART::save_control_register`] and [`UART::enter_loopback`].
+///
+/// The guard borrows the `UART` handle it was created from. Because `UART` is `Copy`, that borrow
+/// doesn't stop other copies of the handle from changing the control register while the guard is alive;
+/// any such changes are overwritten with the saved value when the guard is dropped.
+#[must_use = "the saved control register is restored as soon as the guard is dropped"]
+pub struct RegisterGuard<'a, T: BaseAddress> {
+    uart: &'a UART<T>,
+    saved: ControlRegister,
+}
+
+impl<T: BaseAddress> RegisterGuard<'_, T> {
+    /// The control register value that will be restored.
+    pub fn saved(&self) -> ControlRegister {
+        self.saved
+    }
+}
+
+impl<T: BaseAddress> Drop for RegisterGuard<'_, T> {
+    fn drop(&mut self) {
+        self.uart.write_control_register(self.saved);
+    }
+}
+
+impl<T: BaseAddress> UART<T> {
+    /// Saves the current control register and returns a guard that restores it when dropped.
+    pub fn save_control_register(&self) -> RegisterGuard<'_, T> {
+        RegisterGuard {
+            uart: self,
+            saved: self.read_control_register(),
+        }
+    }
+
+    /// Enables loopback mode until the returned guard is dropped, at which point the previous control register is restored.
+    pub fn enter_loopback(&self) -> RegisterGuard<'_, T> {
+        let guard = self.save_control_register();
+        self.write_control_register(guard.saved.with_loopback_enable(true));
+        guard
+    }
+
+    /// Applies `set` to the control register, runs `body` and then restores the previous control register.
+    ///
+    /// The control register is restored even if `body` panics and the panic unwinds,
+    /// so e.g. loopback or a test mode can't be left enabled by an early exit:
+    ///
+    /// ```ignore
+    /// let ok = uart.with_control_bit(|cr| cr.with_loopback_enable(true), || run_self_test(&uart));
+    /// ```
+    pub fn with_control_bit<R>(
+        &self,
+        set: impl FnOnce(ControlRegister) -> ControlRegister,
+        body: impl FnOnce() -> R,
+    ) -> R {
+        let guard = self.save_control_register();
+        self.write_control_register(set(guard.saved));
+        body()
+    }
+}
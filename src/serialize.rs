@@ -0,0 +1,332 @@
+//! `serde` support for the register types, behind the `serde` feature.
+//!
+//! Every register is (de)serialized as a struct of its named fields rather than as its raw value,
+//! so e.g. a JSON register dump is readable on its own. Reserved bits aren't part of any field and so aren't kept.
+//! A field holding a value it can't decode, such as a zero divisor or a reserved FIFO level encoding, is kept as its
+//! raw bits instead, so any register value can be dumped, including the reset values of an unconfigured UART.
+//! Those fields are wrapped in an enum: `{"Value": 13}` for a divisor that decodes and `{"Raw": 0}` for one that
+//! doesn't, in JSON.
+use bitstuff::ints::{u4, u6};
+use core::num::{NonZeroU8, NonZeroU16};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+use crate::registrers::{self, FIFOLevelSelect, ParityPolarity, StopBits, WordLength};
+
+/// How a field type is represented when (de)serialized.
+trait Repr: Sized {
+    type Repr;
+    fn to_repr(self) -> Self::Repr;
+    /// Returns `None` if `repr` is out of range for the field.
+    fn from_repr(repr: Self::Repr) -> Option<Self>;
+}
+
+macro_rules! identity_repr {
+    ($($ty:ty),*) => {
+        $(
+            impl Repr for $ty {
+                type Repr = Self;
+                fn to_repr(self) -> Self {
+                    self
+                }
+                fn from_repr(repr: Self) -> Option<Self> {
+                    Some(repr)
+                }
+            }
+        )*
+    };
+}
+
+identity_repr!(
+    bool,
+    u8,
+    NonZeroU8,
+    NonZeroU16,
+    WordLength,
+    StopBits,
+    ParityPolarity,
+    FIFOLevelSelect
+);
+
+macro_rules! small_int_repr {
+    ($($ty:ident: $max:literal),*) => {
+        $(
+            impl Repr for $ty {
+                type Repr = u8;
+                fn to_repr(self) -> u8 {
+                    u8::from(self)
+                }
+                fn from_repr(repr: u8) -> Option<Self> {
+                    (repr <= $max).then(|| $ty::new(repr))
+                }
+            }
+        )*
+    };
+}
+
+small_int_repr!(u4: 0xF, u6: 0x3F);
+
+/// The representation of a field whose getter can fail: the decoded value, or the raw bits of the field if it doesn't
+/// decode.
+#[derive(Serialize, Deserialize)]
+enum Decoded<T> {
+    Value(T),
+    Raw(u16),
+}
+
+/// Implements `Serialize` and `Deserialize` for each register through a private struct with the same name and fields.
+///
+/// Each field is given as `getter: Type => builder`. The fields whose getter returns a `Result` are marked with
+/// `#[fallible(bits = lo..=hi)]`, the bits they occupy, which is where a raw value is put back on deserialization.
+macro_rules! serde_registers {
+    ($(
+        $register:ident {
+            $($(#[fallible(bits = $lo:literal..=$hi:literal)])? $field:ident: $ty:ty => $with:ident,)*
+        }
+    )*) => {
+        $(
+            const _: () = {
+                #[derive(Serialize, Deserialize)]
+                struct $register {
+                    $($field: serde_registers!(@repr $ty $(, $lo)?),)*
+                }
+
+                impl Serialize for registrers::$register {
+                    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                        $register {
+                            $($field: serde_registers!(@get self, $field $(, $lo)?),)*
+                        }
+                        .serialize(serializer)
+                    }
+                }
+
+                impl<'de> Deserialize<'de> for registrers::$register {
+                    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                        let fields = $register::deserialize(deserializer)?;
+                        let register = Self::from_bits(0);
+                        $(
+                            let register = serde_registers!(
+                                @set register, fields.$field, $field: $ty => $with $(, $lo, $hi)?
+                            )
+                            .ok_or_else(|| {
+                                <D::Error as de::Error>::custom(concat!("invalid value for ", stringify!($field)))
+                            })?;
+                        )*
+                        Ok(register)
+                    }
+                }
+            };
+        )*
+    };
+    (@repr $ty:ty) => {
+        <$ty as Repr>::Repr
+    };
+    (@repr $ty:ty, $lo:literal) => {
+        Decoded<<$ty as Repr>::Repr>
+    };
+    (@get $register:ident, $field:ident) => {
+        $register.$field().to_repr()
+    };
+    (@get $register:ident, $field:ident, $lo:literal) => {
+        match $register.$field() {
+            Ok(value) => Decoded::Value(value.to_repr()),
+            Err(raw) => Decoded::Raw(u16::from(raw)),
+        }
+    };
+    (@set $register:ident, $value:expr, $field:ident: $ty:ty => $with:ident) => {
+        <$ty as Repr>::from_repr($value).map(|value| $register.$with(value))
+    };
+    (@set $register:ident, $value:expr, $field:ident: $ty:ty => $with:ident, $lo:literal, $hi:literal) => {
+        match $value {
+            Decoded::Value(value) => <$ty as Repr>::from_repr(value).map(|value| $register.$with(value)),
+            Decoded::Raw(raw) if u32::from(raw) < 1 << ($hi - $lo + 1) => Some(Self::from_bits(
+                (u32::from($register.bits()) | u32::from(raw) << $lo) as _,
+            )),
+            Decoded::Raw(_) => None,
+        }
+    };
+}
+
+serde_registers! {
+    DataRegister {
+        overrun_error: bool => with_overrun_error,
+        break_error: bool => with_break_error,
+        parity_error: bool => with_parity_error,
+        framing_error: bool => with_framing_error,
+        data: u8 => with_data,
+    }
+    ReceiveStatusRegister {
+        overrun_error: bool => with_overrun_error,
+        break_error: bool => with_break_error,
+        parity_error: bool => with_parity_error,
+        framing_error: bool => with_framing_error,
+    }
+    FlagRegister {
+        ring_indicator: bool => with_ring_indicator,
+        transmit_fifo_empty: bool => with_transmit_fifo_empty,
+        receive_fifo_full: bool => with_receive_fifo_full,
+        transmit_fifo_full: bool => with_transmit_fifo_full,
+        receive_fifo_empty: bool => with_receive_fifo_empty,
+        uart_busy: bool => with_uart_busy,
+        data_carrier_detect: bool => with_data_carrier_detect,
+        data_set_ready: bool => with_data_set_ready,
+        clear_to_send: bool => with_clear_to_send,
+    }
+    IrDALowPowerRegister {
+        #[fallible(bits = 0..=7)] low_power_divisor_value: NonZeroU8 => with_low_power_divisor_value,
+    }
+    IntegerBaudRateDivisorRegister {
+        #[fallible(bits = 0..=15)] integer_baud_rate_divisor: NonZeroU16 => with_integer_baud_rate_divisor,
+    }
+    FractionalBaudRateDivisorRegister {
+        fractional_baud_rate_divisor: u6 => with_fractional_baud_rate_divisor,
+    }
+    LineControlRegister {
+        stick_parity: bool => with_stick_parity,
+        word_length: WordLength => with_word_length,
+        enable_fifos: bool => with_enable_fifos,
+        stop_bits: StopBits => with_stop_bits,
+        parity_polarity: ParityPolarity => with_parity_polarity,
+        parity_enable: bool => with_parity_enable,
+        send_break: bool => with_send_break,
+    }
+    ControlRegister {
+        cts_hardware_flow_control_enable: bool => with_cts_hardware_flow_control_enable,
+        rts_hardware_flow_control_enable: bool => with_rts_hardware_flow_control_enable,
+        out2: bool => with_out2,
+        out1: bool => with_out1,
+        request_to_send: bool => with_request_to_send,
+        data_transmit_ready: bool => with_data_transmit_ready,
+        receive_enable: bool => with_receive_enable,
+        transmit_enable: bool => with_transmit_enable,
+        loopback_enable: bool => with_loopback_enable,
+        sir_enable: bool => with_sir_enable,
+        uart_enable: bool => with_uart_enable,
+    }
+    InterruptFIFOLevelSelectRegister {
+        #[fallible(bits = 3..=5)] receive_interrupt_fifo_level_select: FIFOLevelSelect => with_receive_interrupt_fifo_level_select,
+        #[fallible(bits = 0..=2)] transmit_interrupt_fifo_level_select: FIFOLevelSelect => with_transmit_interrupt_fifo_level_select,
+    }
+    InterruptMaskSetClearRegister {
+        overrun_error_interrupt_mask: bool => with_overrun_error_interrupt_mask,
+        break_error_interrupt_mask: bool => with_break_error_interrupt_mask,
+        parity_error_interrupt_mask: bool => with_parity_error_interrupt_mask,
+        framing_error_interrupt_mask: bool => with_framing_error_interrupt_mask,
+        receive_timeout_interrupt_mask: bool => with_receive_timeout_interrupt_mask,
+        transmit_interrupt_mask: bool => with_transmit_interrupt_mask,
+        receive_interrupt_mask: bool => with_receive_interrupt_mask,
+        n_uartdsr_modem_interrupt_mask: bool => with_n_uartdsr_modem_interrupt_mask,
+        n_uartdcd_modem_interrupt_mask: bool => with_n_uartdcd_modem_interrupt_mask,
+        n_uartcts_modem_interrupt_mask: bool => with_n_uartcts_modem_interrupt_mask,
+        n_uartri_modem_interrupt_mask: bool => with_n_uartri_modem_interrupt_mask,
+    }
+    RawInterruptStatusRegister {
+        overrun_error_raw_interrupt: bool => with_overrun_error_raw_interrupt,
+        break_error_raw_interrupt: bool => with_break_error_raw_interrupt,
+        parity_error_raw_interrupt: bool => with_parity_error_raw_interrupt,
+        framing_error_raw_interrupt: bool => with_framing_error_raw_interrupt,
+        receive_timeout_raw_interrupt: bool => with_receive_timeout_raw_interrupt,
+        transmit_raw_interrupt: bool => with_transmit_raw_interrupt,
+        receive_raw_interrupt: bool => with_receive_raw_interrupt,
+        n_uartdsr_modem_raw_interrupt: bool => with_n_uartdsr_modem_raw_interrupt,
+        n_uartdcd_modem_raw_interrupt: bool => with_n_uartdcd_modem_raw_interrupt,
+        n_uartcts_modem_raw_interrupt: bool => with_n_uartcts_modem_raw_interrupt,
+        n_uartri_modem_raw_interrupt: bool => with_n_uartri_modem_raw_interrupt,
+    }
+    MaskedInterruptStatusRegister {
+        overrun_error_interrupt_status: bool => with_overrun_error_interrupt_status,
+        break_error_interrupt_status: bool => with_break_error_interrupt_status,
+        parity_error_interrupt_status: bool => with_parity_error_interrupt_status,
+        framing_error_interrupt_status: bool => with_framing_error_interrupt_status,
+        receive_timeout_interrupt_status: bool => with_receive_timeout_interrupt_status,
+        transmit_interrupt_status: bool => with_transmit_interrupt_status,
+        receive_interrupt_status: bool => with_receive_interrupt_status,
+        n_uartdsr_modem_interrupt_status: bool => with_n_uartdsr_modem_interrupt_status,
+        n_uartdcd_modem_interrupt_status: bool => with_n_uartdcd_modem_interrupt_status,
+        n_uartcts_modem_interrupt_status: bool => with_n_uartcts_modem_interrupt_status,
+        n_uartri_modem_interrupt_status: bool => with_n_uartri_modem_interrupt_status,
+    }
+    InterruptClearRegister {
+        clear_overrun_error_interrupt: bool => with_clear_overrun_error_interrupt,
+        clear_break_error_interrupt: bool => with_clear_break_error_interrupt,
+        clear_parity_error_interrupt: bool => with_clear_parity_error_interrupt,
+        clear_framing_error_interrupt: bool => with_clear_framing_error_interrupt,
+        clear_receive_timeout_interrupt: bool => with_clear_receive_timeout_interrupt,
+        clear_transmit_interrupt: bool => with_clear_transmit_interrupt,
+        clear_receive_interrupt: bool => with_clear_receive_interrupt,
+        clear_n_uartdsr_modem_interrupt: bool => with_clear_n_uartdsr_modem_interrupt,
+        clear_n_uartdcd_modem_interrupt: bool => with_clear_n_uartdcd_modem_interrupt,
+        clear_n_uartcts_modem_interrupt: bool => with_clear_n_uartcts_modem_interrupt,
+        clear_n_uartri_modem_interrupt: bool => with_clear_n_uartri_modem_interrupt,
+    }
+    DMAControlRegister {
+        dma_on_error: bool => with_dma_on_error,
+        transmit_dma_enable: bool => with_transmit_dma_enable,
+        receive_dma_enable: bool => with_receive_dma_enable,
+    }
+    TestControlRegister {
+        sir_test: bool => with_sir_test,
+        test_fifo_enable: bool => with_test_fifo_enable,
+        integration_test_enable: bool => with_integration_test_enable,
+    }
+    IntegrationTestInputRegister {
+        n_uartcts: bool => with_n_uartcts,
+        n_uartdcd: bool => with_n_uartdcd,
+        n_uartdsr: bool => with_n_uartdsr,
+        n_uartri: bool => with_n_uartri,
+        uart_txdmaclr: bool => with_uart_txdmaclr,
+        uart_rxdmaclr: bool => with_uart_rxdmaclr,
+        sirin: bool => with_sirin,
+        uart_rxd: bool => with_uart_rxd,
+    }
+    IntegrationTestOutputRegister {
+        uart_msintr: bool => with_uart_msintr,
+        uart_rxintr: bool => with_uart_rxintr,
+        uart_txintr: bool => with_uart_txintr,
+        uart_rtintr: bool => with_uart_rtintr,
+        uart_eintr: bool => with_uart_eintr,
+        uart_intr: bool => with_uart_intr,
+        n_uartout2: bool => with_n_uartout2,
+        n_uartout1: bool => with_n_uartout1,
+        n_uartrts: bool => with_n_uartrts,
+        n_uartdtr: bool => with_n_uartdtr,
+        uart_txdmasreq: bool => with_uart_txdmasreq,
+        uart_txdmabreq: bool => with_uart_txdmabreq,
+        uart_rxdmasreq: bool => with_uart_rxdmasreq,
+        uart_rxdmabreq: bool => with_uart_rxdmabreq,
+        n_sirout: bool => with_n_sirout,
+        uart_txd: bool => with_uart_txd,
+    }
+    IntegrationTestDataRegister {
+        overrun_error: bool => with_overrun_error,
+        break_error: bool => with_break_error,
+        parity_error: bool => with_parity_error,
+        framing_error: bool => with_framing_error,
+        data: u8 => with_data,
+    }
+    PeripheralId0Register {
+        part_number_0: u8 => with_part_number_0,
+    }
+    PeripheralId1Register {
+        designer_0: u4 => with_designer_0,
+        part_number_1: u4 => with_part_number_1,
+    }
+    PeripheralId2Register {
+        revision: u4 => with_revision,
+        designer_1: u4 => with_designer_1,
+    }
+    PeripheralId3Register {
+        configuration: u8 => with_configuration,
+    }
+    PrimeCellId0Register {
+        primecell_id_0: u8 => with_primecell_id_0,
+    }
+    PrimeCellId1Register {
+        primecell_id_1: u8 => with_primecell_id_1,
+    }
+    PrimeCellId2Register {
+        primecell_id_2: u8 => with_primecell_id_2,
+    }
+    PrimeCellId3Register {
+        primecell_id_3: u8 => with_primecell_id_3,
+    }
+}
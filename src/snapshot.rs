@@ -0,0 +1,75 @@
+//! A copy of every register that can be read without side effects, for logging the state of the UART.
+use crate::{
+    BaseAddress, UART,
+    registrers::{
+        ControlRegister, DMAControlRegister, FlagRegister, FractionalBaudRateDivisorRegister,
+        IntegerBaudRateDivisorRegister, InterruptFIFOLevelSelectRegister,
+        InterruptMaskSetClearRegister, IrDALowPowerRegister, LineControlRegister,
+        MaskedInterruptStatusRegister, RawInterruptStatusRegister, ReceiveStatusRegister,
+    },
+};
+
+/// The registers of a UART as they were read by [`UART::snapshot`].
+///
+/// The data register is deliberately missing: reading it pops the receive FIFO, so a snapshot would lose a character.
+/// The error flags of the last character read are in `receive_status` instead.
+/// The write-only registers, the test registers and the integration test registers aren't included either.
+///
+/// The `Debug` output lists every field of every register, which makes this a one call dump for logging:
+///
+/// ```ignore
+/// if let Err(error) = uart.read_byte() {
+///     log::error!("{error:?} with the UART in {:#?}", uart.snapshot());
+/// }
+/// ```
+///
+/// With the `serde` feature a snapshot can be serialized too, e.g. for a register dump viewer. This works for any
+/// state of the UART: fields that don't decode, such as the zero divisors of an unconfigured UART, are kept as their
+/// raw bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UartSnapshot {
+    pub receive_status: ReceiveStatusRegister,
+    pub flag: FlagRegister,
+    pub irda_low_power: IrDALowPowerRegister,
+    pub integer_baud_rate_divisor: IntegerBaudRateDivisorRegister,
+    pub fractional_baud_rate_divisor: FractionalBaudRateDivisorRegister,
+    pub line_control: LineControlRegister,
+    pub control: ControlRegister,
+    pub interrupt_fifo_level_select: InterruptFIFOLevelSelectRegister,
+    pub interrupt_mask_set_clear: InterruptMaskSetClearRegister,
+    pub raw_interrupt_status: RawInterruptStatusRegister,
+    pub masked_interrupt_status: MaskedInterruptStatusRegister,
+    pub dma_control: DMAControlRegister,
+    /// The combined peripheral identification registers, see [`UART::read_peripheral_id`].
+    pub peripheral_id: u32,
+    /// The combined PrimeCell identification registers, see [`UART::read_primecell_id`].
+    pub primecell_id: u32,
+}
+
+impl<T: BaseAddress, A> UART<T, A> {
+    /// Reads every register listed in [`UartSnapshot`], one after the other.
+    ///
+    /// None of these reads have side effects, in particular the data register isn't read, so this is safe to call
+    /// at any time. The registers are read one at a time, so a flag that changes in between (e.g. BUSY)
+    /// may disagree with the interrupt status read after it.
+    pub fn snapshot(&self) -> UartSnapshot {
+        UartSnapshot {
+            receive_status: self.read_receive_status_register(),
+            flag: self.read_flag_register(),
+            irda_low_power: self.read_irda_low_power_register(),
+            integer_baud_rate_divisor: self.read_integer_baud_rate_divisor_register(),
+            fractional_baud_rate_divisor: self.read_fractional_baud_rate_divisor_register(),
+            line_control: self.read_line_control_register(),
+            control: self.read_control_register(),
+            interrupt_fifo_level_select: self.read_interrupt_fifo_level_select_register(),
+            interrupt_mask_set_clear: self.read_interrupt_mask_set_clear_register(),
+            raw_interrupt_status: self.read_raw_interrupt_status_register(),
+            masked_interrupt_status: self.read_masked_interrupt_status_register(),
+            dma_control: self.read_dma_control_register(),
+            peripheral_id: self.read_peripheral_id(),
+            primecell_id: self.read_primecell_id(),
+        }
+    }
+}
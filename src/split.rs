@@ -0,0 +1,103 @@
+//! Separate transmit and receive handles, e.g. to move the receiver into an interrupt handler.
+use crate::{
+    BaseAddress, UART,
+    interrupts::Interrupts,
+    registrers::{DataRegister, FlagRegister},
+};
+
+/// The transmit half of a [`UART`], see [`UART::split`].
+#[derive(Debug)]
+pub struct Tx<T: BaseAddress> {
+    uart: UART<T>,
+}
+
+/// The receive half of a [`UART`], see [`UART::split`].
+///
+/// Receiving pops the receive FIFO, which a [`ReadOnly`](crate::access::ReadOnly) handle can't do, so this holds a
+/// full handle and only exposes the receive side of it.
+#[derive(Debug)]
+pub struct Rx<T: BaseAddress> {
+    uart: UART<T>,
+}
+
+impl<T: BaseAddress> UART<T> {
+    /// Splits the UART into a handle that can only transmit and one that can only receive.
+    ///
+    /// Both halves use the same base address, so unlike e.g. two DMA channels they aren't truly disjoint:
+    /// they share the flag register and the interrupt registers, and nothing stops other copies of the `UART`
+    /// (which is `Copy`) from touching either direction. Keeping the halves apart is up to the caller.
+    ///
+    /// The receive half can only receive and read the flags. The transmit half also controls the transmit interrupt,
+    /// which lives in the interrupt mask register next to the receive interrupts, so changing it is a read-modify-write
+    /// of a register the receive side may care about too, see [`Tx::set_tx_interrupt_enabled`].
+    pub fn split(self) -> (Tx<T>, Rx<T>) {
+        (Tx { uart: self }, Rx { uart: self })
+    }
+}
+
+impl<T: BaseAddress> Tx<T> {
+    /// Writes every byte of `data`, blocking while the transmit FIFO is full. See [`UART::write_bytes`].
+    pub fn write_bytes(&self, data: &[u8]) {
+        self.uart.write_bytes(data);
+    }
+
+    /// Writes a single byte if there is room in the transmit FIFO, returning whether it was written.
+    pub fn try_write_byte(&self, byte: u8) -> bool {
+        if self.uart.read_flag_register().transmit_fifo_full() {
+            return false;
+        }
+        self.uart
+            .write_data_register(DataRegister::default().with_data(byte));
+        true
+    }
+
+    /// Blocks until everything written has been transmitted. See [`UART::flush`].
+    pub fn flush(&self) {
+        self.uart.flush();
+    }
+
+    /// Reads the flag register, e.g. to check the transmit FIFO state.
+    pub fn read_flag_register(&self) -> FlagRegister {
+        self.uart.read_flag_register()
+    }
+
+    /// Enables or disables the transmit interrupt, leaving the other interrupt masks as they are.
+    ///
+    /// This is a read-modify-write of the interrupt mask register, which also holds the masks of the receive
+    /// interrupts. If code elsewhere (e.g. the owner of the receive half holding on to a copy of the `UART`) changes
+    /// the mask register at the same time, one of the two changes can be lost.
+    pub fn set_tx_interrupt_enabled(&self, on: bool) {
+        self.uart
+            .update_interrupt_mask_set_clear_register(|imsc| imsc.with_transmit_interrupt_mask(on));
+    }
+
+    /// Clears the transmit interrupt, without affecting any other interrupt.
+    pub fn clear_tx_interrupt(&self) {
+        self.uart.clear_interrupts(Interrupts::TX);
+    }
+}
+
+impl<T: BaseAddress> Rx<T> {
+    /// Reads the next received character, including its error flags, or `None` if the receive FIFO is empty.
+    pub fn read(&self) -> Option<DataRegister> {
+        if self.uart.read_flag_register().receive_fifo_empty() {
+            None
+        } else {
+            Some(self.uart.read_data_register())
+        }
+    }
+
+    /// Blocks until a character has been received and returns it, including its error flags.
+    pub fn read_blocking(&self) -> DataRegister {
+        loop {
+            if let Some(data) = self.read() {
+                return data;
+            }
+        }
+    }
+
+    /// Reads the flag register, e.g. to check the receive FIFO state.
+    pub fn read_flag_register(&self) -> FlagRegister {
+        self.uart.read_flag_register()
+    }
+}
@@ -0,0 +1,76 @@
+//! Running totals of receive errors, e.g. for monitoring the health of a serial link.
+use crate::{
+    BaseAddress, UART,
+    registrers::{DataRegister, ReceiveStatusRegister},
+};
+
+/// Caller-owned running totals of the four receive error conditions.
+///
+/// The counters saturate rather than wrap.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ErrorCounters {
+    /// Number of overrun errors seen.
+    pub overrun: u32,
+    /// Number of break conditions seen.
+    pub break_: u32,
+    /// Number of parity errors seen.
+    pub parity: u32,
+    /// Number of framing errors seen.
+    pub framing: u32,
+}
+
+impl ErrorCounters {
+    pub const fn new() -> Self {
+        ErrorCounters {
+            overrun: 0,
+            break_: 0,
+            parity: 0,
+            framing: 0,
+        }
+    }
+
+    fn add(&mut self, overrun: bool, break_: bool, parity: bool, framing: bool) {
+        self.overrun = self.overrun.saturating_add(overrun as u32);
+        self.break_ = self.break_.saturating_add(break_ as u32);
+        self.parity = self.parity.saturating_add(parity as u32);
+        self.framing = self.framing.saturating_add(framing as u32);
+    }
+
+    /// Adds the errors flagged in a receive status register value.
+    pub fn accumulate(&mut self, status: &ReceiveStatusRegister) {
+        self.add(
+            status.overrun_error(),
+            status.break_error(),
+            status.parity_error(),
+            status.framing_error(),
+        );
+    }
+
+    /// Adds the errors flagged alongside a received character in a data register value.
+    pub fn accumulate_data(&mut self, data: &DataRegister) {
+        self.add(
+            data.overrun_error(),
+            data.break_error(),
+            data.parity_error(),
+            data.framing_error(),
+        );
+    }
+
+    /// The sum of all counters.
+    pub fn total(&self) -> u32 {
+        self.overrun
+            .saturating_add(self.break_)
+            .saturating_add(self.parity)
+            .saturating_add(self.framing)
+    }
+}
+
+impl<T: BaseAddress> UART<T> {
+    /// Reads the data register, like [`UART::read_data_register`], and adds the errors flagged for the received character to `counters`.
+    #[must_use = "reading the data register pops the receive FIFO, the character is lost if it isn't used"]
+    pub fn read_data_register_counted(&self, counters: &mut ErrorCounters) -> DataRegister {
+        let data = self.read_data_register();
+        counters.accumulate_data(&data);
+        data
+    }
+}
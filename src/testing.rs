@@ -0,0 +1,437 @@
+//! An in-memory register file, to run driver code on the host without a PL011.
+//!
+//! [`RegisterFile`] stands in for the 4 KiB register window. A `&RegisterFile` is a [`BaseAddress`], so
+//! `UART::new(&file)` gives a handle with every `read_*`/`write_*` method working against plain memory,
+//! and every access is recorded so a test can check what the driver did:
+//!
+//! ```
+//! use pl011_uart_registers::{offsets, testing::RegisterFile};
+//!
+//! let file = RegisterFile::new();
+//! let uart = file.uart();
+//! uart.set_baud_rate(24_000_000, 115_200).unwrap();
+//! assert_eq!(file.get(offsets::INTEGER_BAUD_RATE_DIVISOR), 13);
+//! assert_eq!(file.get(offsets::FRACTIONAL_BAUD_RATE_DIVISOR), 1);
+//! // 13 + 1/64 is slightly below the exact 13.02
+//! assert_eq!(uart.effective_baud_rate(24_000_000), 115_246);
+//! ```
+//!
+//! [`MockUart`] builds on it with a simulated receive FIFO and transmitter, to test protocol code
+//! that talks to the UART through the data and flag registers.
+//!
+//! Interrupt handlers can be run against a mock by setting the masked interrupt status register by hand:
+//!
+//! ```
+//! use pl011_uart_registers::{
+//!     UART,
+//!     interrupts::{Interrupts, UartHandlers},
+//!     offsets,
+//!     testing::MockUart,
+//! };
+//!
+//! struct Handlers<'a> {
+//!     uart: UART<&'a MockUart>,
+//!     calls: Vec<&'static str>,
+//!     received: Vec<u8>,
+//! }
+//!
+//! impl UartHandlers for Handlers<'_> {
+//!     fn on_rx(&mut self) {
+//!         self.calls.push("rx");
+//!         self.received.extend(self.uart.drain_rx().filter_map(Result::ok));
+//!     }
+//!     fn on_overrun(&mut self) {
+//!         self.calls.push("overrun");
+//!     }
+//!     fn on_tx(&mut self) {
+//!         self.calls.push("tx");
+//!     }
+//! }
+//!
+//! let mock = MockUart::new();
+//! let uart = mock.uart();
+//! let mut handlers = Handlers { uart, calls: Vec::new(), received: Vec::new() };
+//! mock.push_received(b"hi");
+//! let pending = Interrupts::RX | Interrupts::OVERRUN;
+//! mock.registers().set(offsets::MASKED_INTERRUPT_STATUS, pending.bits().into());
+//!
+//! assert_eq!(uart.service_interrupts(&mut handlers), pending);
+//! assert_eq!(handlers.calls, ["rx", "overrun"]);
+//! assert_eq!(handlers.received, b"hi");
+//! assert_eq!(mock.registers().writes_to(offsets::INTERRUPT_CLEAR), [0x410]);
+//! ```
+//!
+//! Only available with the `test-util` feature, which enables the `std` feature.
+use core::cell::{Cell, RefCell};
+use std::{collections::VecDeque, vec::Vec};
+
+use crate::{
+    BaseAddress, MmioAccess, UART,
+    fifo::FIFO_DEPTH,
+    offsets,
+    registrers::{DataRegister, FlagRegister, ReceiveStatusRegister},
+};
+
+/// The size of the register window in 32-bit words.
+const WORDS: usize = offsets::REGISTER_WINDOW / 4;
+
+/// One register access, as recorded by [`RegisterFile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    /// A read of `value` from the register at `offset`.
+    Read { offset: usize, value: u32 },
+    /// A write of `value` to the register at `offset`.
+    Write { offset: usize, value: u32 },
+}
+
+/// A register window backed by memory, which records every access made through it.
+///
+/// Every register starts out as zero and simply holds whatever was last written to it.
+/// None of the side effects of the hardware are simulated: reading the data register doesn't pop anything,
+/// and writing the interrupt clear register doesn't clear anything.
+///
+/// ```
+/// use pl011_uart_registers::{interrupts::Interrupts, testing::RegisterFile};
+///
+/// let file = RegisterFile::new();
+/// file.uart().clear_interrupts(Interrupts::RX | Interrupts::RX_TIMEOUT);
+/// assert_eq!(file.writes_to(0x44), [0x50]);
+/// ```
+#[derive(Debug)]
+pub struct RegisterFile {
+    words: [Cell<u32>; WORDS],
+    accesses: RefCell<Vec<Access>>,
+}
+
+impl Default for RegisterFile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegisterFile {
+    pub fn new() -> Self {
+        RegisterFile {
+            words: core::array::from_fn(|_| Cell::new(0)),
+            accesses: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Returns a `UART` handle whose registers are this register file.
+    pub fn uart(&self) -> UART<&Self> {
+        UART::new(self)
+    }
+
+    /// The value of the register at `offset`, without recording an access.
+    pub fn get(&self, offset: usize) -> u32 {
+        self.words[offset / 4].get()
+    }
+
+    /// Sets the register at `offset` to `value`, without recording an access.
+    ///
+    /// Use this to put the registers in the state a test needs, e.g. to set flags in the flag register.
+    pub fn set(&self, offset: usize, value: u32) {
+        self.words[offset / 4].set(value);
+    }
+
+    /// Every access made through a `UART` handle so far, oldest first.
+    pub fn accesses(&self) -> Vec<Access> {
+        self.accesses.borrow().clone()
+    }
+
+    /// Returns the accesses made so far and clears the record.
+    pub fn take_accesses(&self) -> Vec<Access> {
+        self.accesses.take()
+    }
+
+    /// The values written to the register at `offset` so far, oldest first.
+    pub fn writes_to(&self, offset: usize) -> Vec<u32> {
+        self.accesses
+            .borrow()
+            .iter()
+            .filter_map(|access| match *access {
+                Access::Write { offset: o, value } if o == offset => Some(value),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn record(&self, access: Access) {
+        self.accesses.borrow_mut().push(access);
+    }
+
+    fn offset_of(&self, addr: usize) -> usize {
+        let offset = addr.wrapping_sub(self.words.as_ptr() as usize);
+        assert!(
+            offset < WORDS * 4 && offset.is_multiple_of(4),
+            "access at {offset:#x} is outside of the register window or not word aligned"
+        );
+        offset
+    }
+}
+
+impl BaseAddress for &RegisterFile {
+    fn base_address(self) -> usize {
+        self.words.as_ptr() as usize
+    }
+}
+
+impl MmioAccess for &RegisterFile {
+    unsafe fn read<R>(&self, addr: usize) -> R {
+        let offset = self.offset_of(addr);
+        let value = self.get(offset);
+        self.record(Access::Read { offset, value });
+        unsafe { from_word(value) }
+    }
+
+    unsafe fn write<R>(&self, addr: usize, value: R) {
+        let offset = self.offset_of(addr);
+        let value = unsafe { to_word(value) };
+        self.set(offset, value);
+        self.record(Access::Write { offset, value });
+    }
+}
+
+/// Converts a register word to an `R` of 1, 2 or 4 bytes.
+///
+/// Registers are taken from the low bits of their word, like on a little-endian bus.
+///
+/// # Safety
+/// Every value of the low bits must be a valid `R`.
+unsafe fn from_word<R>(value: u32) -> R {
+    unsafe {
+        match size_of::<R>() {
+            1 => core::mem::transmute_copy(&(value as u8)),
+            2 => core::mem::transmute_copy(&(value as u16)),
+            4 => core::mem::transmute_copy(&value),
+            size => panic!("unsupported register width of {size} bytes"),
+        }
+    }
+}
+
+/// Converts an `R` of 1, 2 or 4 bytes to a register word, see [`from_word`].
+///
+/// # Safety
+/// `R` must not contain any padding.
+unsafe fn to_word<R>(value: R) -> u32 {
+    unsafe {
+        match size_of::<R>() {
+            1 => core::mem::transmute_copy::<R, u8>(&value) as u32,
+            2 => core::mem::transmute_copy::<R, u16>(&value) as u32,
+            4 => core::mem::transmute_copy::<R, u32>(&value),
+            size => panic!("unsupported register width of {size} bytes"),
+        }
+    }
+}
+
+/// A simulated PL011 with a receive FIFO to push characters into and a transmitter that collects what was sent.
+///
+/// Like [`RegisterFile`] a `&MockUart` is a [`BaseAddress`], and every access is recorded in [`MockUart::registers`].
+/// On top of that:
+/// - reading the data register pops the next character pushed with [`MockUart::push_received`], and puts its
+///   error flags in the receive status register,
+/// - writing the data register queues the character in a simulated transmit FIFO of [`FIFO_DEPTH`] entries and
+///   appends it to [`MockUart::transmitted`]. A write while the FIFO is full is dropped like the hardware would,
+///   and counted in [`MockUart::dropped_tx_bytes`],
+/// - every read of the flag register finishes sending one queued character, so a driver that polls the flags
+///   between writes always finds room, while one that writes without looking overflows the FIFO,
+/// - the receive FIFO empty, transmit FIFO empty, transmit FIFO full and busy flags follow the simulated FIFOs.
+///   The other flags read as whatever was set with [`MockUart::set_flags`].
+///
+/// ```
+/// use core::fmt::Write;
+/// use pl011_uart_registers::{blocking::ReceiveError, registrers::DataRegister, testing::MockUart};
+///
+/// let mock = MockUart::new();
+/// let mut uart = mock.uart();
+/// write!(uart, "{} + {} = {}", 1, 2, 1 + 2).unwrap();
+/// assert_eq!(mock.take_transmitted(), b"1 + 2 = 3");
+///
+/// write!(uart.crlf_writer(), "a\nb").unwrap();
+/// assert_eq!(mock.take_transmitted(), b"a\r\nb");
+/// write!(uart, "a\nb").unwrap();
+/// assert_eq!(mock.transmitted(), b"a\nb");
+///
+/// mock.push_received(b"ok");
+/// assert_eq!(uart.read_byte(), Ok(b'o'));
+/// assert_eq!(uart.read_byte(), Ok(b'k'));
+/// assert_eq!(uart.try_read_byte(), None);
+///
+/// mock.push_received(b"a");
+/// mock.push_received_word(DataRegister::default().with_data(b'b').with_parity_error(true));
+/// mock.push_received(b"c");
+/// let drained: Vec<_> = uart.drain_rx().collect();
+/// assert_eq!(drained, [Ok(b'a'), Err(ReceiveError::Parity), Ok(b'c')]);
+/// assert_eq!(mock.pending_received(), 0);
+///
+/// let (tx, rx) = uart.split();
+/// mock.push_received(b"?");
+/// assert_eq!(rx.read().map(|data| data.data()), Some(b'?'));
+/// tx.set_tx_interrupt_enabled(true);
+/// assert!(uart.read_interrupt_mask_set_clear_register().transmit_interrupt_mask());
+/// ```
+///
+/// A driver that doesn't check the transmit FIFO full flag before writing shows up as dropped characters:
+///
+/// ```
+/// use pl011_uart_registers::{fifo::FIFO_DEPTH, registrers::DataRegister, testing::MockUart};
+///
+/// let mock = MockUart::new();
+/// let uart = mock.uart();
+/// for byte in 0..100 {
+///     uart.write_data_register(DataRegister::default().with_data(byte));
+/// }
+/// assert_eq!(mock.dropped_tx_bytes(), 100 - FIFO_DEPTH);
+/// assert_eq!(mock.transmitted().len(), FIFO_DEPTH);
+/// assert!(uart.read_flag_register().transmit_fifo_full());
+///
+/// // write_bytes polls the flags, so nothing is lost
+/// let mock = MockUart::new();
+/// mock.uart().write_bytes(&[0x55; 100]);
+/// assert_eq!(mock.dropped_tx_bytes(), 0);
+/// assert_eq!(mock.transmitted(), [0x55; 100]);
+/// ```
+#[derive(Debug)]
+pub struct MockUart {
+    registers: RegisterFile,
+    received: RefCell<VecDeque<DataRegister>>,
+    transmitted: RefCell<Vec<u8>>,
+    /// The number of characters in the simulated transmit FIFO that haven't been sent yet.
+    tx_fifo_level: Cell<usize>,
+    dropped_tx_bytes: Cell<usize>,
+}
+
+impl Default for MockUart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockUart {
+    pub fn new() -> Self {
+        MockUart {
+            registers: RegisterFile::new(),
+            received: RefCell::new(VecDeque::new()),
+            transmitted: RefCell::new(Vec::new()),
+            tx_fifo_level: Cell::new(0),
+            dropped_tx_bytes: Cell::new(0),
+        }
+    }
+
+    /// Returns a `UART` handle whose registers are this mock.
+    pub fn uart(&self) -> UART<&Self> {
+        UART::new(self)
+    }
+
+    /// The register file underneath, with the record of every access.
+    pub fn registers(&self) -> &RegisterFile {
+        &self.registers
+    }
+
+    /// Adds characters to the end of the simulated receive FIFO, without any errors.
+    pub fn push_received(&self, data: &[u8]) {
+        let mut received = self.received.borrow_mut();
+        received.extend(
+            data.iter()
+                .map(|&byte| DataRegister::default().with_data(byte)),
+        );
+    }
+
+    /// Adds a received character to the end of the simulated receive FIFO, including its error flags.
+    pub fn push_received_word(&self, word: DataRegister) {
+        self.received.borrow_mut().push_back(word);
+    }
+
+    /// The number of characters in the simulated receive FIFO that haven't been read yet.
+    pub fn pending_received(&self) -> usize {
+        self.received.borrow().len()
+    }
+
+    /// Every character written to the data register so far, oldest first.
+    pub fn transmitted(&self) -> Vec<u8> {
+        self.transmitted.borrow().clone()
+    }
+
+    /// Returns the characters written so far and clears them.
+    pub fn take_transmitted(&self) -> Vec<u8> {
+        self.transmitted.take()
+    }
+
+    /// The number of characters written to the data register while the transmit FIFO was full, which were dropped.
+    pub fn dropped_tx_bytes(&self) -> usize {
+        self.dropped_tx_bytes.get()
+    }
+
+    /// The number of characters in the simulated transmit FIFO that haven't been sent yet.
+    pub fn tx_fifo_level(&self) -> usize {
+        self.tx_fifo_level.get()
+    }
+
+    /// Sets the value the flag register reads as, apart from the flags that follow the simulated FIFOs.
+    ///
+    /// Setting `transmit_fifo_full` or `uart_busy` forces them on regardless of the transmit FIFO, e.g. to make writers
+    /// or [`UART::flush`] wait forever.
+    pub fn set_flags(&self, flags: FlagRegister) {
+        self.registers.set(offsets::FLAG, flags.bits());
+    }
+
+    /// Applies `f` to the value the flag register reads as, see [`MockUart::set_flags`].
+    pub fn update_flags(&self, f: impl FnOnce(FlagRegister) -> FlagRegister) {
+        self.set_flags(f(self.flags()));
+    }
+
+    fn flags(&self) -> FlagRegister {
+        let flags = FlagRegister::from_bits(self.registers.get(offsets::FLAG));
+        let level = self.tx_fifo_level.get();
+        flags
+            .with_receive_fifo_empty(self.received.borrow().is_empty())
+            .with_transmit_fifo_empty(level == 0)
+            .with_transmit_fifo_full(flags.transmit_fifo_full() || level >= FIFO_DEPTH)
+            .with_uart_busy(flags.uart_busy() || level > 0)
+    }
+}
+
+impl BaseAddress for &MockUart {
+    fn base_address(self) -> usize {
+        self.registers.base_address()
+    }
+}
+
+impl MmioAccess for &MockUart {
+    unsafe fn read<R>(&self, addr: usize) -> R {
+        let offset = self.registers.offset_of(addr);
+        let value = match offset {
+            offsets::DATA => {
+                let word = self.received.borrow_mut().pop_front().unwrap_or_default();
+                let status = ReceiveStatusRegister::from_bits(word.bits() >> 8);
+                self.registers.set(offsets::RECEIVE_STATUS, status.bits());
+                word.bits()
+            }
+            offsets::FLAG => {
+                let flags = self.flags();
+                // time passes between two polls, enough to send one character
+                self.tx_fifo_level
+                    .set(self.tx_fifo_level.get().saturating_sub(1));
+                flags.bits()
+            }
+            _ => return unsafe { (&self.registers).read(addr) },
+        };
+        self.registers.record(Access::Read { offset, value });
+        unsafe { from_word(value) }
+    }
+
+    unsafe fn write<R>(&self, addr: usize, value: R) {
+        if self.registers.offset_of(addr) == offsets::DATA {
+            let word = DataRegister::from_bits(unsafe { to_word::<R>(value) });
+            if self.flags().transmit_fifo_full() {
+                self.dropped_tx_bytes.set(self.dropped_tx_bytes.get() + 1);
+            } else {
+                self.tx_fifo_level.set(self.tx_fifo_level.get() + 1);
+                self.transmitted.borrow_mut().push(word.data());
+            }
+            unsafe { (&self.registers).write(addr, word) }
+        } else {
+            unsafe { (&self.registers).write(addr, value) }
+        }
+    }
+}
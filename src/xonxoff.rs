@@ -0,0 +1,128 @@
+//! Software (XON/XOFF) flow control on top of the polled register accesses.
+//!
+//! The remote pauses our transmitter by sending [`XOFF`] and resumes it with [`XON`].
+//! In the other direction, received bytes are kept in a small buffer and we send XOFF
+//! when it is filling up and XON once it has drained again.
+use crate::{BaseAddress, UART, registrers::DataRegister};
+
+/// Resume transmission, DC1.
+pub const XON: u8 = 0x11;
+/// Pause transmission, DC3.
+pub const XOFF: u8 = 0x13;
+
+/// A UART with XON/XOFF flow control and an `N` byte receive buffer.
+///
+/// Received XON/XOFF bytes are consumed by the wrapper and never show up in the received data,
+/// so this is only suitable for text protocols where those values can't appear in the payload.
+///
+/// The wrapper only sees received bytes when one of its methods is called, so it has to be polled
+/// regularly (e.g. via [`XonXoff::poll`]) for the flow control to be timely.
+#[derive(Debug)]
+pub struct XonXoff<T: BaseAddress, const N: usize = 64> {
+    uart: UART<T>,
+    buffer: [u8; N],
+    head: usize,
+    len: usize,
+    /// the remote sent XOFF and we must not transmit
+    paused: bool,
+    /// we sent XOFF and haven't followed it up with an XON yet
+    sent_xoff: bool,
+}
+
+impl<T: BaseAddress, const N: usize> XonXoff<T, N> {
+    /// Wraps `uart`, which should already be configured and enabled.
+    pub const fn new(uart: UART<T>) -> Self {
+        const { assert!(N >= 4, "the receive buffer needs at least 4 bytes") };
+        XonXoff {
+            uart,
+            buffer: [0; N],
+            head: 0,
+            len: 0,
+            paused: false,
+            sent_xoff: false,
+        }
+    }
+
+    /// Returns the wrapped UART, dropping any buffered received bytes.
+    pub fn into_inner(self) -> UART<T> {
+        self.uart
+    }
+
+    /// Returns whether the remote has paused our transmitter with an XOFF.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Returns the number of received bytes waiting in the buffer.
+    pub fn buffered(&self) -> usize {
+        self.len
+    }
+
+    /// writes a flow control byte, bypassing the paused state
+    fn send_control(&self, byte: u8) {
+        while self.uart.read_flag_register().transmit_fifo_full() {}
+        self.uart
+            .write_data_register(DataRegister::default().with_data(byte));
+    }
+
+    /// Moves everything from the receive FIFO into the buffer, handling XON/XOFF along the way,
+    /// and sends an XOFF if the buffer is getting full.
+    ///
+    /// Bytes that arrive while the buffer is completely full are dropped.
+    pub fn poll(&mut self) {
+        while self.uart.read_flag_register().can_receive() {
+            match self.uart.read_data_register().data() {
+                XOFF => self.paused = true,
+                XON => self.paused = false,
+                byte if self.len < N => {
+                    self.buffer[(self.head + self.len) % N] = byte;
+                    self.len += 1;
+                }
+                _ => {}
+            }
+        }
+        if !self.sent_xoff && self.len >= N * 3 / 4 {
+            self.send_control(XOFF);
+            self.sent_xoff = true;
+        }
+    }
+
+    /// Returns the next received byte, or `None` if nothing has been received.
+    ///
+    /// Sends an XON once the buffer has drained to a quarter after an earlier XOFF.
+    pub fn read_byte(&mut self) -> Option<u8> {
+        self.poll();
+        if self.len == 0 {
+            return None;
+        }
+        let byte = self.buffer[self.head];
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        if self.sent_xoff && self.len <= N / 4 {
+            self.send_control(XON);
+            self.sent_xoff = false;
+        }
+        Some(byte)
+    }
+
+    /// Writes `byte`, blocking while the remote has paused us or the transmit FIFO is full.
+    ///
+    /// Bytes received while waiting are buffered, not lost, as long as there is room.
+    pub fn write_byte(&mut self, byte: u8) {
+        loop {
+            self.poll();
+            if !self.paused && self.uart.read_flag_register().can_transmit() {
+                break;
+            }
+        }
+        self.uart
+            .write_data_register(DataRegister::default().with_data(byte));
+    }
+
+    /// Writes every byte of `data`, see [`XonXoff::write_byte`].
+    pub fn write_bytes(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.write_byte(byte);
+        }
+    }
+}